@@ -1,8 +1,19 @@
 use getopts::{Fail, Matches, Options};
+use kdl::{KdlDocument, KdlEntry};
+use kq::Edit;
 use std::env;
 
 const CARGO_PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+pub enum OutputFormat {
+    /// Every result is printed as KDL.
+    Kdl,
+    /// All results are printed as a single JSON array.
+    Json,
+    /// Every result is printed as JSON on its own line.
+    JsonLines,
+}
+
 pub struct Args {
     opts: Options,
     matches: Matches,
@@ -17,6 +28,51 @@ impl Args {
 
         opts.optflag("h", "help", "print this help menu");
         opts.optflag("v", "version", "print the version");
+        opts.optflag(
+            "H",
+            "with-filename",
+            "print the file name in front of each output line",
+        );
+        opts.optflag(
+            "",
+            "with-location",
+            "print file:line:col in front of each match",
+        );
+        opts.optflag(
+            "e",
+            "exit-status",
+            "exit with status 1 when nothing matched",
+        );
+        opts.optflag(
+            "p",
+            "paths",
+            "print the path of every match instead of the match",
+        );
+        opts.optflag(
+            "",
+            "ignore-case",
+            "compare names, type annotations and strings regardless of case",
+        );
+        opts.optopt(
+            "o",
+            "output",
+            "output format: kdl (default), json or jsonl",
+            "FORMAT",
+        );
+        opts.optflag("", "set", "set what the selector matches to the --value");
+        opts.optopt(
+            "",
+            "value",
+            "the KDL value to set, e.g. '\"2.0.0\"'",
+            "VALUE",
+        );
+        opts.optflag("", "delete", "delete what the selector matches");
+        opts.optopt("", "rename", "rename what the selector matches", "NAME");
+        opts.optflag(
+            "i",
+            "in-place",
+            "write edits back to the files instead of printing them",
+        );
 
         let matches = opts.parse(&args[1..])?;
 
@@ -35,12 +91,69 @@ impl Args {
         self.matches.opt_present("v")
     }
 
+    pub fn with_filename(&self) -> bool {
+        self.matches.opt_present("H")
+    }
+
+    pub fn with_location(&self) -> bool {
+        self.matches.opt_present("with-location")
+    }
+
+    pub fn exit_status(&self) -> bool {
+        self.matches.opt_present("e")
+    }
+
+    pub fn paths(&self) -> bool {
+        self.matches.opt_present("p")
+    }
+
+    pub fn ignore_case(&self) -> bool {
+        self.matches.opt_present("ignore-case")
+    }
+
+    pub fn get_output_format(&self) -> Result<OutputFormat, String> {
+        match self.matches.opt_str("o").as_deref() {
+            None | Some("kdl") => Ok(OutputFormat::Kdl),
+            Some("json") => Ok(OutputFormat::Json),
+            Some("jsonl") => Ok(OutputFormat::JsonLines),
+            Some(format) => Err(format!("unknown output format: {}", format)),
+        }
+    }
+
+    /// The edit given by `--set`, `--delete` or `--rename`, if any.
+    pub fn get_edit(&self) -> Result<Option<Edit>, String> {
+        let set = self.matches.opt_present("set");
+        let delete = self.matches.opt_present("delete");
+        let value = self.matches.opt_str("value");
+        let rename = self.matches.opt_str("rename");
+
+        match (set, delete, value, rename) {
+            (false, false, None, None) => Ok(None),
+            (true, false, Some(value), None) => {
+                parse_value(&value).map(|entry| Some(Edit::Set(entry)))
+            }
+            (true, false, None, None) => Err("--set requires a --value".to_owned()),
+            (false, true, None, None) => Ok(Some(Edit::Delete)),
+            (false, false, None, Some(name)) => Ok(Some(Edit::Rename(name))),
+            (false, false, Some(_), None) => Err("--value can only be given with --set".to_owned()),
+            _ => Err("only one of --set, --delete and --rename can be given".to_owned()),
+        }
+    }
+
+    pub fn in_place(&self) -> bool {
+        self.matches.opt_present("i")
+    }
+
     pub fn get_query(&self) -> Option<&String> {
-        self.matches.free.get(0)
+        self.matches.free.first()
+    }
+
+    pub fn get_files(&self) -> &[String] {
+        self.matches.free.get(1..).unwrap_or_default()
     }
 
     pub fn print_help(&self) {
-        let brief = format!("Usage: {} [options] <selector>", self.program);
+        let brief = format!("Usage: {} [options] <selector> [file...]", self.program);
         print!("{}", self.opts.usage(&brief));
     }
 
@@ -48,3 +161,20 @@ impl Args {
         println!("{}", CARGO_PKG_VERSION);
     }
 }
+
+/// Parses a value the way it would be written as the argument of a node, e.g. `"2.0.0"`,
+/// `0xFF` or `(semver)"2.0.0"`.
+fn parse_value(value: &str) -> Result<KdlEntry, String> {
+    let invalid = || format!("invalid value: {}", value);
+    let document = format!("- {}", value)
+        .parse::<KdlDocument>()
+        .map_err(|_| invalid())?;
+
+    match document.nodes() {
+        [node] if node.children().is_none() => match node.entries() {
+            [entry] if entry.name().is_none() => Ok(entry.clone()),
+            _ => Err(invalid()),
+        },
+        _ => Err(invalid()),
+    }
+}
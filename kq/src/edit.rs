@@ -0,0 +1,335 @@
+use crate::parser::{Entity, Mapping};
+use crate::tree::Tree;
+use crate::{Error, Query};
+use kdl::{KdlDocument, KdlEntry, KdlIdentifier, KdlNode, KdlValue};
+
+/// A change that [`Query::edit`] applies to every matched node.
+///
+/// The map operator of the query names what is changed, e.g. `package => prop(edition)`.
+/// Without one, the first argument of a node is set, and the node itself is deleted or renamed.
+/// The update operator of a query (`selector |= val() = 1`) is made of the same changes.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Edit {
+    /// Sets an argument, a property, the type annotation (`tag()`) or the name (`name()`)
+    /// of a node. The entry is only used for its value, type annotation and representation,
+    /// e.g. `0xFF` stays hexadecimal. Missing arguments and properties are added, and when
+    /// an argument is set past the last one, the arguments in between are `null`.
+    Set(KdlEntry),
+    /// Deletes the node, or one of its entries or its type annotation.
+    Delete,
+    /// Renames the node, or one of its properties.
+    Rename(String),
+    /// Appends an argument to the node, only with `values()` or without a map operator.
+    Append(KdlEntry),
+}
+
+impl Query {
+    /// Applies `edit` to every node matched in `document`, and returns the number of matched nodes.
+    ///
+    /// The rest of the document stays byte for byte the same, including comments, blank lines
+    /// and the way numbers and strings are written. Deleted nodes take the comments right above
+    /// them along.
+    pub fn edit(&self, document: &mut KdlDocument, edit: &Edit) -> Result<usize, Error> {
+        let entity = match &self.mapping {
+            None => None,
+            Some((_offset, Mapping::Entity(entity))) => Some(entity),
+            Some((offset, Mapping::Tuple(_))) => {
+                return Err(Error::Unsupported {
+                    offset: *offset,
+                    feature: "editing a tuple".to_owned(),
+                })
+            }
+        };
+        if let Some(feature) = check(edit, entity) {
+            return Err(Error::Unsupported {
+                offset: self.mapping.as_ref().map_or(0, |(offset, _)| *offset),
+                feature,
+            });
+        }
+
+        Ok(self.edit_matches(document, &[(entity, edit)]))
+    }
+
+    /// Applies the update operator of the query (`selector |= prop(dev) = false`) to every node
+    /// matched in `document`, and returns the number of matched nodes.
+    ///
+    /// Like [`Query::edit`], the rest of the document stays byte for byte the same.
+    pub fn update(&self, document: &mut KdlDocument) -> usize {
+        let edits = self
+            .update
+            .iter()
+            .map(|(entity, edit)| (Some(entity), edit))
+            .collect::<Vec<_>>();
+        self.edit_matches(document, &edits)
+    }
+
+    /// Tells whether the query has an update operator, see [`Query::update`].
+    pub fn has_update(&self) -> bool {
+        !self.update.is_empty()
+    }
+
+    fn edit_matches(
+        &self,
+        document: &mut KdlDocument,
+        edits: &[(Option<&Entity>, &Edit)],
+    ) -> usize {
+        // the indices of every matched node from the top of the document, which sort in
+        // document order
+        let mut positions = {
+            let tree = Tree::new(document.nodes());
+            self.select_in(&tree)
+                .into_iter()
+                .filter_map(|node| tree.lineage(node))
+                .map(|lineage| lineage.iter().map(|position| position.index).collect())
+                .collect::<Vec<Vec<usize>>>()
+        };
+        positions.sort();
+
+        // later siblings and descendants first, so that deleting a node does not move the others
+        for position in positions.iter().rev() {
+            let (index, parent) = position.split_last().unwrap();
+            let siblings = container_mut(document, parent);
+
+            for (entity, edit) in edits {
+                match (edit, entity) {
+                    (Edit::Delete, None) => remove(siblings, *index, !parent.is_empty()),
+                    (edit, entity) => edit_node(&mut siblings.nodes_mut()[*index], edit, *entity),
+                }
+            }
+        }
+
+        positions.len()
+    }
+}
+
+/// Tells which edits can not be applied to `entity`, before anything is changed.
+pub(crate) fn check(edit: &Edit, entity: Option<&Entity>) -> Option<String> {
+    match (edit, entity) {
+        (Edit::Set(entry), Some(Entity::NodeName)) => match entry.value() {
+            KdlValue::RawString(_) | KdlValue::String(_) => None,
+            _ => Some("setting `name()` to a value other than a string".to_owned()),
+        },
+        (Edit::Set(entry), Some(Entity::TypeTag)) => match entry.value() {
+            KdlValue::RawString(_) | KdlValue::String(_) | KdlValue::Null => None,
+            _ => Some("setting `tag()` to a value other than a string or null".to_owned()),
+        },
+        (Edit::Set(_), Some(entity @ (Entity::Props | Entity::Values))) => {
+            Some(format!("setting {}", describe(entity)))
+        }
+        (Edit::Delete, Some(entity @ Entity::NodeName)) => {
+            Some(format!("deleting {}", describe(entity)))
+        }
+        (
+            Edit::Rename(_),
+            Some(entity @ (Entity::TypeTag | Entity::Props | Entity::Val(_) | Entity::Values)),
+        ) => Some(format!("renaming {}", describe(entity))),
+        (Edit::Append(_), Some(entity)) if *entity != Entity::Values => {
+            Some(format!("appending to {}", describe(entity)))
+        }
+        _ => None,
+    }
+}
+
+fn describe(entity: &Entity) -> String {
+    match entity {
+        Entity::NodeName => "`name()`".to_owned(),
+        Entity::PropName(name) => format!("`prop({})`", name),
+        Entity::Props => "`props()`".to_owned(),
+        Entity::TypeTag => "`tag()`".to_owned(),
+        Entity::Val(index) => format!("`val({})`", index),
+        Entity::Values => "`values()`".to_owned(),
+    }
+}
+
+/// Removes a node along with the comments right above it, while blank lines and the comments
+/// they separate from the node are left to the next one. When the node was the last on its
+/// line, the one before it ends the line, without its `;`.
+fn remove(siblings: &mut KdlDocument, index: usize, in_children: bool) {
+    let first_in_block = in_children && index == 0;
+    let at_line_start = match index {
+        0 => !in_children,
+        _ => siblings.nodes()[index - 1]
+            .trailing()
+            .and_then(ending_line_break)
+            .is_some(),
+    };
+    let removed = siblings.nodes_mut().remove(index);
+    let kept = kept_whitespace(
+        removed.leading().unwrap_or(""),
+        at_line_start,
+        first_in_block,
+    );
+    let line_break = removed.trailing().and_then(ending_line_break);
+    let ends_line = line_break.is_some() || (index == siblings.nodes().len() && !in_children);
+
+    if index > 0 && !at_line_start && ends_line {
+        // the node before ends the line instead, without leaving its `;` dangling
+        let previous = &mut siblings.nodes_mut()[index - 1];
+        let trailing = previous.trailing().unwrap_or("").trim_end();
+        let trailing = trailing.strip_suffix(';').unwrap_or(trailing).trim_end();
+        let trailing = format!("{}{}", trailing, line_break.unwrap_or(""));
+        previous.set_trailing(trailing);
+    }
+
+    if let Some(next) = siblings.nodes_mut().get_mut(index) {
+        let leading = format!("{}{}", kept, next.leading().unwrap_or(""));
+        next.set_leading(leading);
+    } else if index == 0 && in_children {
+        // an emptied block is printed as `{}`
+        siblings.set_leading("");
+        siblings.set_trailing("");
+    }
+}
+
+fn ending_line_break(text: &str) -> Option<&'static str> {
+    if text.ends_with("\r\n") {
+        Some("\r\n")
+    } else if text.ends_with('\n') {
+        Some("\n")
+    } else {
+        None
+    }
+}
+
+/// The part of the whitespace in front of a removed node that does not belong to it:
+/// everything up to its last blank line, and the rest of the line of `{` for the first
+/// node of a block.
+fn kept_whitespace(leading: &str, at_line_start: bool, first_in_block: bool) -> &str {
+    if first_in_block && !leading.contains('\n') {
+        return leading;
+    }
+
+    let mut end = 0;
+    let mut line_start = 0;
+    for (i, _) in leading.match_indices('\n') {
+        let blank = (line_start > 0 || at_line_start) && leading[line_start..i].trim().is_empty();
+        if blank || (first_in_block && line_start == 0) {
+            end = i + 1;
+        }
+        line_start = i + 1;
+    }
+    &leading[..end]
+}
+
+fn edit_node(node: &mut KdlNode, edit: &Edit, entity: Option<&Entity>) {
+    match (edit, entity.unwrap_or(&Entity::Val(0))) {
+        (Edit::Append(entry), _) => node.push(new_entry(None, entry)),
+        (Edit::Set(entry), Entity::NodeName) => {
+            if let Some(name) = identifier(entry) {
+                node.set_name(name);
+            }
+        }
+        (Edit::Set(entry), Entity::TypeTag) => *node.ty_mut() = identifier(entry),
+        (Edit::Set(entry), Entity::PropName(name)) => {
+            match node
+                .entries()
+                .iter()
+                .rposition(|entry| is_property(entry, name))
+            {
+                Some(i) => {
+                    let old = &node.entries()[i];
+                    node.entries_mut()[i] = replace(old, old.name().cloned(), entry);
+                }
+                None => node.push(new_entry(Some(name.as_str().into()), entry)),
+            }
+        }
+        (Edit::Set(entry), Entity::Val(index)) => match position_of_argument(node, *index) {
+            Some(i) => node.entries_mut()[i] = replace(&node.entries()[i], None, entry),
+            None => {
+                // the arguments in between are `null`, so that the value is at `val(index)`
+                let arguments = node.entries().iter().filter(|old| old.name().is_none());
+                for _ in arguments.count()..*index {
+                    node.push(KdlEntry::new(KdlValue::Null));
+                }
+                node.push(new_entry(None, entry));
+            }
+        },
+        (Edit::Delete, Entity::PropName(name)) => {
+            node.entries_mut().retain(|entry| !is_property(entry, name))
+        }
+        (Edit::Delete, Entity::Val(index)) => {
+            if let Some(i) = position_of_argument(node, *index) {
+                node.entries_mut().remove(i);
+            }
+        }
+        (Edit::Delete, Entity::TypeTag) => *node.ty_mut() = None,
+        (Edit::Delete, Entity::Props) => node.entries_mut().retain(|entry| entry.name().is_none()),
+        (Edit::Delete, Entity::Values) => node.entries_mut().retain(|entry| entry.name().is_some()),
+        (Edit::Rename(name), Entity::PropName(property)) => {
+            // only the last of duplicated properties counts, so the others are dropped
+            let last = node
+                .entries()
+                .iter()
+                .rposition(|entry| is_property(entry, property));
+            if let Some(last) = last {
+                let old = &node.entries()[last];
+                node.entries_mut()[last] = replace(old, Some(name.as_str().into()), old);
+
+                let mut i = 0;
+                node.entries_mut().retain(|entry| {
+                    let keep = i == last || !is_property(entry, property);
+                    i += 1;
+                    keep
+                });
+            }
+        }
+        (Edit::Rename(name), _) => node.set_name(name.as_str()),
+        // rejected by `check`, or deletions of whole nodes, which are done by the caller
+        (Edit::Set(_), Entity::Props | Entity::Values) | (Edit::Delete, Entity::NodeName) => {}
+    }
+}
+
+fn identifier(entry: &KdlEntry) -> Option<KdlIdentifier> {
+    match entry.value() {
+        KdlValue::RawString(string) | KdlValue::String(string) => Some(string.as_str().into()),
+        _ => None,
+    }
+}
+
+fn is_property(entry: &KdlEntry, name: &str) -> bool {
+    entry.name().map(KdlIdentifier::value) == Some(name)
+}
+
+fn position_of_argument(node: &KdlNode, index: usize) -> Option<usize> {
+    node.entries()
+        .iter()
+        .enumerate()
+        .filter(|(_i, entry)| entry.name().is_none())
+        .nth(index)
+        .map(|(i, _entry)| i)
+}
+
+/// A new entry with the value, type annotation and representation of `value`.
+fn new_entry(name: Option<KdlIdentifier>, value: &KdlEntry) -> KdlEntry {
+    let mut entry = match name {
+        Some(name) => KdlEntry::new_prop(name, value.value().clone()),
+        None => KdlEntry::new(value.value().clone()),
+    };
+    if let Some(ty) = value.ty() {
+        entry.set_ty(ty.clone());
+    }
+    if let Some(repr) = value.value_repr() {
+        entry.set_value_repr(repr);
+    }
+    entry
+}
+
+/// A new entry in place of `entry`, keeping the whitespace around it.
+fn replace(entry: &KdlEntry, name: Option<KdlIdentifier>, value: &KdlEntry) -> KdlEntry {
+    let mut replacement = new_entry(name, value);
+    if let Some(leading) = entry.leading() {
+        replacement.set_leading(leading);
+    }
+    if let Some(trailing) = entry.trailing() {
+        replacement.set_trailing(trailing);
+    }
+    replacement
+}
+
+/// The document or the children block that holds the siblings of a node.
+fn container_mut<'a>(document: &'a mut KdlDocument, parent: &[usize]) -> &'a mut KdlDocument {
+    parent.iter().fold(document, |document, i| {
+        document.nodes_mut()[*i].ensure_children()
+    })
+}
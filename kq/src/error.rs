@@ -0,0 +1,65 @@
+use kdl::KdlError;
+use std::error;
+use std::fmt;
+
+/// An error returned when a selector or a document cannot be used for a query.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// The selector is not valid syntax, `offset` is the byte offset within the selector
+    /// where parsing failed and `expected` lists what would have been accepted there.
+    Selector {
+        offset: usize,
+        expected: Vec<&'static str>,
+    },
+    /// The selector is valid syntax, but uses a feature that kq does not support.
+    Unsupported { offset: usize, feature: String },
+    /// A regular expression of the `~=` operator that cannot be compiled, `offset` is
+    /// the byte offset of the pattern within the selector.
+    Pattern { offset: usize, message: String },
+    /// The document is not valid KDL, `offset` is the byte offset within the document.
+    Document { offset: usize, message: String },
+}
+
+impl Error {
+    /// The byte offset within the selector or the document where the error occurred.
+    pub fn offset(&self) -> usize {
+        match self {
+            Error::Selector { offset, .. }
+            | Error::Unsupported { offset, .. }
+            | Error::Pattern { offset, .. }
+            | Error::Document { offset, .. } => *offset,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Selector { expected, .. } => {
+                write!(f, "invalid selector")?;
+                if let Some((last, init)) = expected.split_last() {
+                    write!(f, ", expected ")?;
+                    if !init.is_empty() {
+                        write!(f, "{} or ", init.join(", "))?;
+                    }
+                    write!(f, "{}", last)?;
+                }
+                Ok(())
+            }
+            Error::Unsupported { feature, .. } => write!(f, "{} is not supported", feature),
+            Error::Pattern { message, .. } => write!(f, "invalid regular expression, {}", message),
+            Error::Document { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<KdlError> for Error {
+    fn from(error: KdlError) -> Self {
+        Error::Document {
+            offset: error.span.offset(),
+            message: error.to_string(),
+        }
+    }
+}
@@ -1,7 +1,72 @@
-use crate::Operator;
+use crate::{Case, Operator};
 use kdl::KdlValue;
+use regex::Regex;
+use std::borrow::Cow;
+use std::cmp::Ordering;
 
-pub(crate) fn evaluate(lhs: &KdlValue, operator: &Operator, rhs: &KdlValue) -> bool {
+/// The type annotation of a right-hand side that compares strings as semantic versions,
+/// e.g. `[val() >= (semver)"1.2.0"]`.
+pub(crate) const SEMVER: &str = "semver";
+
+/// A view of [`KdlValue`] that ignores how a value was written in the document,
+/// e.g. `0xFF` and `255` are both `Value::Int(255)`, `r"raw"` and `"raw"` are both strings.
+enum Value<'a> {
+    Int(i64),
+    Float(f64),
+    String(Cow<'a, str>),
+    Version(Version<'a>),
+    Boolean(bool),
+    Null,
+}
+
+impl<'a> From<&'a KdlValue> for Value<'a> {
+    fn from(value: &'a KdlValue) -> Self {
+        match value {
+            KdlValue::RawString(string) | KdlValue::String(string) => {
+                Value::String(Cow::Borrowed(string))
+            }
+            KdlValue::Base2(int)
+            | KdlValue::Base8(int)
+            | KdlValue::Base10(int)
+            | KdlValue::Base16(int) => Value::Int(*int),
+            KdlValue::Base10Float(float) => Value::Float(*float),
+            KdlValue::Bool(boolean) => Value::Boolean(*boolean),
+            KdlValue::Null => Value::Null,
+        }
+    }
+}
+
+impl Value<'_> {
+    /// Lowercases strings so that they compare regardless of case.
+    fn fold_case(self) -> Self {
+        match self {
+            Value::String(string) => Value::String(Cow::Owned(string.to_lowercase())),
+            value => value,
+        }
+    }
+}
+
+/// Compares `lhs` with `rhs`, as semantic versions when `rhs` is annotated with `(semver)`,
+/// in which case values that are not versions never match.
+pub(crate) fn evaluate(
+    lhs: &KdlValue,
+    operator: &Operator,
+    type_tag: Option<&str>,
+    rhs: &KdlValue,
+    case: Case,
+) -> bool {
+    let (lhs, rhs) = match type_tag {
+        Some(SEMVER) => match (Version::from_value(lhs), Version::from_value(rhs)) {
+            (Some(lhs), Some(rhs)) => (Value::Version(lhs), Value::Version(rhs)),
+            _ => return false,
+        },
+        _ => (Value::from(lhs), Value::from(rhs)),
+    };
+    let (lhs, rhs) = match case {
+        Case::Sensitive => (lhs, rhs),
+        Case::Insensitive => (lhs.fold_case(), rhs.fold_case()),
+    };
+    let (lhs, rhs) = (&lhs, &rhs);
     match operator {
         Operator::Contains => contains(lhs, rhs),
         Operator::EndsWith => ends_with(lhs, rhs),
@@ -10,156 +75,506 @@ pub(crate) fn evaluate(lhs: &KdlValue, operator: &Operator, rhs: &KdlValue) -> b
         Operator::GreaterThanOrEqualTo => greater_than_or_equal_to(lhs, rhs),
         Operator::LessThan => less_than(lhs, rhs),
         Operator::LessThanOrEqualTo => less_than_or_equal_to(lhs, rhs),
+        Operator::Matches(pattern) => matches_pattern(lhs, &pattern.0),
         Operator::NotEqual => not_equal(lhs, rhs),
         Operator::StartsWith => starts_with(lhs, rhs),
     }
 }
 
-fn contains(lhs: &KdlValue, rhs: &KdlValue) -> bool {
+fn contains(lhs: &Value, rhs: &Value) -> bool {
     match lhs {
-        KdlValue::String(lhs) => match rhs {
-            KdlValue::String(rhs) => lhs.contains(rhs),
-            KdlValue::Int(_) | KdlValue::Float(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
+        Value::String(lhs) => match rhs {
+            Value::String(rhs) => lhs.contains(rhs.as_ref()),
+            Value::Int(_)
+            | Value::Float(_)
+            | Value::Version(_)
+            | Value::Boolean(_)
+            | Value::Null => false,
         },
-        KdlValue::Int(_) | KdlValue::Boolean(_) | KdlValue::Null | KdlValue::Float(_) => false,
+        Value::Int(_) | Value::Float(_) | Value::Version(_) | Value::Boolean(_) | Value::Null => {
+            false
+        }
     }
 }
 
-fn ends_with(lhs: &KdlValue, rhs: &KdlValue) -> bool {
+fn ends_with(lhs: &Value, rhs: &Value) -> bool {
     match lhs {
-        KdlValue::Int(_) | KdlValue::Float(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
-        KdlValue::String(lhs) => match rhs {
-            KdlValue::String(rhs) => lhs.ends_with(rhs),
-            KdlValue::Int(_) | KdlValue::Float(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
+        Value::Int(_) | Value::Float(_) | Value::Version(_) | Value::Boolean(_) | Value::Null => {
+            false
+        }
+        Value::String(lhs) => match rhs {
+            Value::String(rhs) => lhs.ends_with(rhs.as_ref()),
+            Value::Int(_)
+            | Value::Float(_)
+            | Value::Version(_)
+            | Value::Boolean(_)
+            | Value::Null => false,
         },
     }
 }
 
-fn equal(lhs: &KdlValue, rhs: &KdlValue) -> bool {
+fn equal(lhs: &Value, rhs: &Value) -> bool {
     match lhs {
-        KdlValue::Int(lhs) => match rhs {
-            KdlValue::Int(rhs) => lhs == rhs,
-            KdlValue::Float(_) | KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => {
-                false
-            }
-        },
-        KdlValue::Float(lhs) => match rhs {
-            #[allow(clippy::float_cmp)]
-            KdlValue::Float(rhs) => lhs == rhs,
-            KdlValue::Int(_) | KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
+        Value::Int(_) | Value::Float(_) | Value::Version(_) => {
+            compare(lhs, rhs) == Some(Ordering::Equal)
+        }
+        Value::String(lhs) => match rhs {
+            Value::String(rhs) => lhs == rhs,
+            Value::Int(_)
+            | Value::Float(_)
+            | Value::Version(_)
+            | Value::Boolean(_)
+            | Value::Null => false,
         },
-        KdlValue::String(lhs) => match rhs {
-            KdlValue::String(rhs) => lhs == rhs,
-            KdlValue::Int(_) | KdlValue::Float(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
+        Value::Boolean(lhs) => match rhs {
+            Value::Boolean(rhs) => lhs == rhs,
+            Value::Int(_)
+            | Value::Float(_)
+            | Value::String(_)
+            | Value::Version(_)
+            | Value::Null => false,
         },
-        KdlValue::Boolean(lhs) => match rhs {
-            KdlValue::Boolean(rhs) => lhs == rhs,
-            KdlValue::Int(_) | KdlValue::Float(_) | KdlValue::String(_) | KdlValue::Null => false,
-        },
-        KdlValue::Null => match rhs {
-            KdlValue::Null => true,
-            KdlValue::Int(_) | KdlValue::Float(_) | KdlValue::String(_) | KdlValue::Boolean(_) => {
-                false
-            }
+        Value::Null => match rhs {
+            Value::Null => true,
+            Value::Int(_)
+            | Value::Float(_)
+            | Value::String(_)
+            | Value::Version(_)
+            | Value::Boolean(_) => false,
         },
     }
 }
 
-fn greater_than(lhs: &KdlValue, rhs: &KdlValue) -> bool {
+fn greater_than(lhs: &Value, rhs: &Value) -> bool {
+    matches!(compare(lhs, rhs), Some(Ordering::Greater))
+}
+
+fn greater_than_or_equal_to(lhs: &Value, rhs: &Value) -> bool {
+    matches!(compare(lhs, rhs), Some(Ordering::Greater | Ordering::Equal))
+}
+
+fn less_than(lhs: &Value, rhs: &Value) -> bool {
+    matches!(compare(lhs, rhs), Some(Ordering::Less))
+}
+
+fn less_than_or_equal_to(lhs: &Value, rhs: &Value) -> bool {
+    matches!(compare(lhs, rhs), Some(Ordering::Less | Ordering::Equal))
+}
+
+fn matches_pattern(lhs: &Value, pattern: &Regex) -> bool {
     match lhs {
-        KdlValue::Int(lhs) => match rhs {
-            KdlValue::Int(rhs) => lhs > rhs,
-            KdlValue::Float(_) | KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => {
-                false
-            }
-        },
-        KdlValue::Float(lhs) => match rhs {
-            KdlValue::Float(rhs) => lhs > rhs,
-            KdlValue::Int(_) | KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
-        },
-        KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
+        Value::String(lhs) => pattern.is_match(lhs),
+        Value::Int(_) | Value::Float(_) | Value::Version(_) | Value::Boolean(_) | Value::Null => {
+            false
+        }
     }
 }
 
-fn greater_than_or_equal_to(lhs: &KdlValue, rhs: &KdlValue) -> bool {
+fn not_equal(lhs: &Value, rhs: &Value) -> bool {
     match lhs {
-        KdlValue::Int(lhs) => match rhs {
-            KdlValue::Int(rhs) => lhs >= rhs,
-            KdlValue::Float(_) | KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => {
-                false
-            }
+        Value::Int(_) | Value::Float(_) => match rhs {
+            // NaN is not equal to any number, not even to itself
+            Value::Int(_) | Value::Float(_) => compare(lhs, rhs) != Some(Ordering::Equal),
+            Value::String(_) | Value::Version(_) | Value::Boolean(_) | Value::Null => false,
+        },
+        Value::Version(_) => match rhs {
+            Value::Version(_) => compare(lhs, rhs) != Some(Ordering::Equal),
+            Value::Int(_)
+            | Value::Float(_)
+            | Value::String(_)
+            | Value::Boolean(_)
+            | Value::Null => false,
         },
-        KdlValue::Float(lhs) => match rhs {
-            KdlValue::Float(rhs) => lhs >= rhs,
-            KdlValue::Int(_) | KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
+        Value::String(lhs) => match rhs {
+            Value::String(rhs) => lhs != rhs,
+            Value::Int(_)
+            | Value::Float(_)
+            | Value::Version(_)
+            | Value::Boolean(_)
+            | Value::Null => false,
         },
-        KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
+        Value::Boolean(lhs) => match rhs {
+            Value::Boolean(rhs) => lhs != rhs,
+            Value::Int(_)
+            | Value::Float(_)
+            | Value::String(_)
+            | Value::Version(_)
+            | Value::Null => false,
+        },
+        Value::Null => false,
     }
 }
 
-fn less_than(lhs: &KdlValue, rhs: &KdlValue) -> bool {
+fn starts_with(lhs: &Value, rhs: &Value) -> bool {
     match lhs {
-        KdlValue::Int(lhs) => match rhs {
-            KdlValue::Int(rhs) => lhs < rhs,
-            KdlValue::Float(_) | KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => {
-                false
-            }
+        Value::String(lhs) => match rhs {
+            Value::String(rhs) => lhs.starts_with(rhs.as_ref()),
+            Value::Int(_)
+            | Value::Float(_)
+            | Value::Version(_)
+            | Value::Boolean(_)
+            | Value::Null => false,
         },
-        KdlValue::Float(lhs) => match rhs {
-            KdlValue::Float(rhs) => lhs < rhs,
-            KdlValue::Int(_) | KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
-        },
-        KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
+        Value::Int(_) | Value::Float(_) | Value::Version(_) | Value::Boolean(_) | Value::Null => {
+            false
+        }
     }
 }
 
-fn less_than_or_equal_to(lhs: &KdlValue, rhs: &KdlValue) -> bool {
-    match lhs {
-        KdlValue::Int(lhs) => match rhs {
-            KdlValue::Int(rhs) => lhs <= rhs,
-            KdlValue::Float(_) | KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => {
-                false
-            }
-        },
-        KdlValue::Float(lhs) => match rhs {
-            KdlValue::Float(rhs) => lhs <= rhs,
-            KdlValue::Int(_) | KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
-        },
-        KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
+/// Orders two numbers, two strings or two versions, anything else is not ordered.
+///
+/// Numbers are ordered by their exact values, whether they are integers or floats. An integer
+/// is not converted to a float to be compared with one, since integers beyond 2^53 would be
+/// rounded, e.g. `9007199254740993` would equal `9007199254740992.0`. Infinities are beyond
+/// every integer, and NaN is not ordered, so that it is neither equal to, less than nor greater
+/// than any number.
+///
+/// Strings are ordered lexicographically by their Unicode code points. That misorders versions,
+/// e.g. `"1.10"` comes before `"1.9"`; annotate versions with `(semver)` to order them properly.
+fn compare(lhs: &Value, rhs: &Value) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (Value::Int(lhs), Value::Int(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::Float(lhs), Value::Float(rhs)) => lhs.partial_cmp(rhs),
+        (Value::Int(lhs), Value::Float(rhs)) => compare_int_to_float(*lhs, *rhs),
+        (Value::Float(lhs), Value::Int(rhs)) => {
+            compare_int_to_float(*rhs, *lhs).map(Ordering::reverse)
+        }
+        (Value::String(lhs), Value::String(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::Version(lhs), Value::Version(rhs)) => Some(lhs.cmp(rhs)),
+        _ => None,
     }
 }
 
-fn not_equal(lhs: &KdlValue, rhs: &KdlValue) -> bool {
-    match lhs {
-        KdlValue::Int(lhs) => match rhs {
-            KdlValue::Int(rhs) => lhs != rhs,
-            KdlValue::Float(_) | KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => {
-                false
+fn compare_int_to_float(int: i64, float: f64) -> Option<Ordering> {
+    // 2^63, the first float beyond `i64::MAX`, while `i64::MIN` is exactly -2^63
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+
+    if float.is_nan() {
+        None
+    } else if float >= LIMIT {
+        Some(Ordering::Less)
+    } else if float < -LIMIT {
+        Some(Ordering::Greater)
+    } else {
+        // within the range of i64, the integral part of a float converts exactly
+        let integral = float.trunc() as i64;
+        let fraction = float.fract();
+        Some(int.cmp(&integral).then(if fraction > 0.0 {
+            Ordering::Less
+        } else if fraction < 0.0 {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }))
+    }
+}
+
+/// A semantic version, see <https://semver.org>, such as `1.2.0-rc.1+build.5`.
+///
+/// Versions are ordered by precedence: a pre-release comes before its release, and build
+/// metadata is ignored. Minor and patch numbers may be left out, `1.2` is `1.2.0`.
+#[derive(Debug, PartialEq, Eq)]
+struct Version<'a> {
+    core: (u64, u64, u64),
+    pre_release: Vec<Identifier<'a>>,
+}
+
+/// A dot-separated identifier of a pre-release, numeric ones come before alphanumeric ones.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Identifier<'a> {
+    Numeric(u64),
+    Alphanumeric(&'a str),
+}
+
+impl<'a> Version<'a> {
+    fn from_value(value: &'a KdlValue) -> Option<Self> {
+        match value {
+            KdlValue::RawString(string) | KdlValue::String(string) => Version::parse(string),
+            _ => None,
+        }
+    }
+
+    fn parse(input: &'a str) -> Option<Self> {
+        let (input, build) = match input.split_once('+') {
+            Some((input, build)) => (input, Some(build)),
+            None => (input, None),
+        };
+        let (core, pre_release) = match input.split_once('-') {
+            Some((core, pre_release)) => (core, Some(pre_release)),
+            None => (input, None),
+        };
+
+        let mut numbers = core.split('.').map(|number| match number {
+            number if is_numeric(number) => number.parse::<u64>().ok(),
+            _ => None,
+        });
+        let core = (
+            numbers.next()??,
+            numbers.next().unwrap_or(Some(0))?,
+            numbers.next().unwrap_or(Some(0))?,
+        );
+        if numbers.next().is_some() || build.is_some_and(|build| !is_dot_separated(build)) {
+            return None;
+        }
+
+        let pre_release = match pre_release {
+            Some(pre_release) if is_dot_separated(pre_release) => pre_release
+                .split('.')
+                .map(|identifier| match identifier.parse::<u64>() {
+                    Ok(number) if is_numeric(identifier) => Identifier::Numeric(number),
+                    _ => Identifier::Alphanumeric(identifier),
+                })
+                .collect(),
+            Some(_) => return None,
+            None => vec![],
+        };
+
+        Some(Version { core, pre_release })
+    }
+}
+
+impl<'a> Ord for Version<'a> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.core.cmp(&other.core).then_with(|| {
+            match (self.pre_release.is_empty(), other.pre_release.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre_release.cmp(&other.pre_release),
             }
-        },
-        KdlValue::Float(lhs) => match rhs {
-            #[allow(clippy::float_cmp)]
-            KdlValue::Float(rhs) => lhs != rhs,
-            KdlValue::Int(_) | KdlValue::String(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
-        },
-        KdlValue::String(lhs) => match rhs {
-            KdlValue::String(rhs) => lhs != rhs,
-            KdlValue::Int(_) | KdlValue::Float(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
-        },
-        KdlValue::Boolean(lhs) => match rhs {
-            KdlValue::Boolean(rhs) => lhs != rhs,
-            KdlValue::Int(_) | KdlValue::Float(_) | KdlValue::String(_) | KdlValue::Null => false,
-        },
-        KdlValue::Null => false,
+        })
     }
 }
 
-fn starts_with(lhs: &KdlValue, rhs: &KdlValue) -> bool {
-    match lhs {
-        KdlValue::String(lhs) => match rhs {
-            KdlValue::String(rhs) => lhs.starts_with(rhs),
-            KdlValue::Int(_) | KdlValue::Float(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
-        },
-        KdlValue::Int(_) | KdlValue::Float(_) | KdlValue::Boolean(_) | KdlValue::Null => false,
+impl<'a> PartialOrd for Version<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn is_numeric(input: &str) -> bool {
+    !input.is_empty() && input.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+/// Tells whether `input` is made of non-empty identifiers of ASCII alphanumerics and hyphens,
+/// separated by dots.
+fn is_dot_separated(input: &str) -> bool {
+    input.split('.').all(|identifier| {
+        !identifier.is_empty()
+            && identifier
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_numbers() {
+        let compare = |lhs, rhs| compare(&Value::from(&lhs), &Value::from(&rhs));
+
+        assert_eq!(
+            compare(KdlValue::Base10(3), KdlValue::Base10Float(1.5)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare(KdlValue::Base10Float(1.5), KdlValue::Base16(3)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare(KdlValue::Base10(2), KdlValue::Base10Float(2.0)),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            compare(KdlValue::Base10(-2), KdlValue::Base10Float(-2.5)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare(KdlValue::Base10(-2), KdlValue::Base10Float(-1.5)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare(
+                KdlValue::Base10(9007199254740993),
+                KdlValue::Base10Float(9007199254740992.0)
+            ),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare(
+                KdlValue::Base10(i64::MAX),
+                KdlValue::Base10Float(9223372036854775807.0)
+            ),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare(
+                KdlValue::Base10(i64::MIN),
+                KdlValue::Base10Float(-9223372036854775808.0)
+            ),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            compare(
+                KdlValue::Base10(i64::MIN),
+                KdlValue::Base10Float(f64::NEG_INFINITY)
+            ),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare(
+                KdlValue::Base10(i64::MAX),
+                KdlValue::Base10Float(f64::INFINITY)
+            ),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare(KdlValue::Base10(0), KdlValue::Base10Float(f64::NAN)),
+            None
+        );
+        assert_eq!(
+            compare(KdlValue::Base10(1), KdlValue::String("1".to_owned())),
+            None
+        );
+        assert_eq!(
+            compare(
+                KdlValue::String("1.10".to_owned()),
+                KdlValue::RawString("1.9".to_owned())
+            ),
+            Some(Ordering::Less)
+        );
+    }
+
+    #[test]
+    fn test_version() {
+        let versions = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.2",
+            "1.10.0",
+            "2",
+        ]
+        .map(|version| Version::parse(version).unwrap());
+
+        for pair in versions.windows(2) {
+            assert_eq!(pair[0].cmp(&pair[1]), Ordering::Less, "{:?}", pair);
+        }
+        assert_eq!(Version::parse("1.0.0+build.1"), Version::parse("1.0.0"));
+        assert_eq!(Version::parse("1.0.0.0"), None);
+        assert_eq!(Version::parse("1.x"), None);
+        assert_eq!(Version::parse("1.0.0-"), None);
+        assert_eq!(Version::parse("1.0.0-rc..1"), None);
+        assert_eq!(Version::parse(""), None);
+    }
+
+    #[test]
+    fn test_evaluate_semver() {
+        let string = |string: &str| KdlValue::String(string.to_owned());
+
+        assert!(evaluate(
+            &string("1.10.0"),
+            &Operator::GreaterThan,
+            Some(SEMVER),
+            &string("1.9.0"),
+            Case::Sensitive
+        ));
+        assert!(!evaluate(
+            &string("1.10.0"),
+            &Operator::GreaterThan,
+            None,
+            &string("1.9.0"),
+            Case::Sensitive
+        ));
+        assert!(evaluate(
+            &string("1.2.0+build"),
+            &Operator::Equal,
+            Some(SEMVER),
+            &string("1.2"),
+            Case::Sensitive
+        ));
+        assert!(!evaluate(
+            &string("latest"),
+            &Operator::NotEqual,
+            Some(SEMVER),
+            &string("1.2.0"),
+            Case::Sensitive
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_nan() {
+        let nan = KdlValue::Base10Float(f64::NAN);
+        let zero = KdlValue::Base10(0);
+
+        assert!(!evaluate(
+            &nan,
+            &Operator::Equal,
+            None,
+            &nan,
+            Case::Sensitive
+        ));
+        assert!(evaluate(
+            &nan,
+            &Operator::NotEqual,
+            None,
+            &nan,
+            Case::Sensitive
+        ));
+        assert!(!evaluate(
+            &nan,
+            &Operator::GreaterThanOrEqualTo,
+            None,
+            &zero,
+            Case::Sensitive
+        ));
+        assert!(!evaluate(
+            &nan,
+            &Operator::LessThanOrEqualTo,
+            None,
+            &zero,
+            Case::Sensitive
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_ignoring_case() {
+        let string = |string: &str| KdlValue::String(string.to_owned());
+
+        assert!(evaluate(
+            &string("Windows"),
+            &Operator::Equal,
+            None,
+            &string("WINDOWS"),
+            Case::Insensitive
+        ));
+        assert!(!evaluate(
+            &string("Windows"),
+            &Operator::Equal,
+            None,
+            &string("WINDOWS"),
+            Case::Sensitive
+        ));
+        assert!(evaluate(
+            &string("x86_64-Apple-Darwin"),
+            &Operator::Contains,
+            None,
+            &string("apple"),
+            Case::Insensitive
+        ));
+        assert!(evaluate(
+            &string("b"),
+            &Operator::GreaterThan,
+            None,
+            &string("A"),
+            Case::Insensitive
+        ));
     }
 }
@@ -0,0 +1,94 @@
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// A KDL document to be queried, either the standard input (`-`) or a file.
+pub enum Input {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Input {
+    /// Expands the file arguments into inputs, reading from the standard input when none is given.
+    ///
+    /// Arguments that are not an existing path but contain glob metacharacters (e.g. `**/*.kdl`)
+    /// are expanded here as well, so patterns work the same way on shells that leave them untouched.
+    pub fn expand(args: &[String]) -> Result<Vec<Input>, Error> {
+        if args.is_empty() {
+            return Ok(vec![Input::Stdin]);
+        }
+
+        let mut inputs = vec![];
+        for arg in args {
+            if arg == "-" {
+                inputs.push(Input::Stdin);
+            } else if Path::new(arg).exists() || !arg.contains(['*', '?', '[']) {
+                inputs.push(Input::File(PathBuf::from(arg)));
+            } else {
+                let paths = glob::glob(arg)
+                    .map_err(|error| Error::new(arg, error))?
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|error| Error::new(arg, error))?;
+                if paths.is_empty() {
+                    return Err(Error::new(arg, "no such file or directory"));
+                }
+                inputs.extend(paths.into_iter().map(Input::File));
+            }
+        }
+
+        Ok(inputs)
+    }
+
+    /// The name shown in front of every output line by `--with-filename`.
+    pub fn name(&self) -> String {
+        match self {
+            Input::Stdin => "(standard input)".to_owned(),
+            Input::File(path) => path.display().to_string(),
+        }
+    }
+
+    pub fn read_to_string(&self) -> Result<String, Error> {
+        match self {
+            Input::Stdin => {
+                let mut buffer = String::new();
+                io::stdin()
+                    .read_to_string(&mut buffer)
+                    .map_err(|error| Error::new(&self.name(), error))?;
+                Ok(buffer)
+            }
+            Input::File(path) => {
+                fs::read_to_string(path).map_err(|error| Error::new(&self.name(), error))
+            }
+        }
+    }
+
+    /// Replaces the content of a file, for `--in-place`.
+    pub fn write(&self, contents: &str) -> Result<(), Error> {
+        match self {
+            Input::Stdin => Err(Error::new(&self.name(), "can not be edited in place")),
+            Input::File(path) => {
+                fs::write(path, contents).map_err(|error| Error::new(&self.name(), error))
+            }
+        }
+    }
+}
+
+/// An error that occurred while reading an input, prefixed by the name of the input.
+#[derive(Debug)]
+pub struct Error(String);
+
+impl Error {
+    pub fn new(name: &str, error: impl fmt::Display) -> Error {
+        Error(format!("{}: {}", name, error))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl error::Error for Error {}
@@ -0,0 +1,81 @@
+use kdl::{KdlEntry, KdlNode, KdlValue};
+use kq::Output;
+use serde_json::{Map, Number, Value};
+
+/// Converts a query result into JSON, nodes become objects with their `name`, `values`,
+/// `properties` and `children`, while mapped values keep their shape.
+///
+/// Type annotations are kept: a node has a `type` next to its `name`, and an annotated value
+/// becomes an object with its `type` and `value`, e.g. `{"type": "u8", "value": 1}`.
+pub fn from_output(output: &Output) -> Value {
+    match output {
+        Output::Node(node) => from_node(node),
+        Output::Value(value) => from_value(value),
+        Output::List(values) => Value::Array(values.iter().map(from_value).collect()),
+        Output::Map(properties) => Value::Object(
+            properties
+                .iter()
+                .map(|(key, value)| (key.clone(), from_value(value)))
+                .collect(),
+        ),
+        Output::Tuple(outputs) => Value::Array(outputs.iter().map(from_output).collect()),
+    }
+}
+
+fn from_node(node: &KdlNode) -> Value {
+    let mut values = vec![];
+    let mut properties = Map::new();
+
+    for entry in node.entries() {
+        match entry.name() {
+            Some(name) => {
+                properties.insert(name.value().to_owned(), from_entry(entry));
+            }
+            None => values.push(from_entry(entry)),
+        }
+    }
+
+    let children = node
+        .children()
+        .map(|children| children.nodes().iter().map(from_node).collect())
+        .unwrap_or_default();
+
+    let mut object = Map::new();
+    object.insert("name".to_owned(), node.name().value().into());
+    if let Some(ty) = node.ty() {
+        object.insert("type".to_owned(), ty.value().into());
+    }
+    object.insert("values".to_owned(), Value::Array(values));
+    object.insert("properties".to_owned(), Value::Object(properties));
+    object.insert("children".to_owned(), Value::Array(children));
+    Value::Object(object)
+}
+
+fn from_entry(entry: &KdlEntry) -> Value {
+    let value = from_value(entry.value());
+    match entry.ty() {
+        Some(ty) => {
+            let mut object = Map::new();
+            object.insert("type".to_owned(), ty.value().into());
+            object.insert("value".to_owned(), value);
+            Value::Object(object)
+        }
+        None => value,
+    }
+}
+
+/// Non-finite floats have no JSON representation and become `null`.
+fn from_value(value: &KdlValue) -> Value {
+    match value {
+        KdlValue::RawString(string) | KdlValue::String(string) => string.as_str().into(),
+        KdlValue::Base2(int)
+        | KdlValue::Base8(int)
+        | KdlValue::Base10(int)
+        | KdlValue::Base16(int) => (*int).into(),
+        KdlValue::Base10Float(float) => Number::from_f64(*float)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        KdlValue::Bool(boolean) => (*boolean).into(),
+        KdlValue::Null => Value::Null,
+    }
+}
@@ -228,15 +228,24 @@ fn unicode(input: &str) -> IResult<&str, char> {
 /// `value := type-annotation? (string | raw_string | number | boolean | 'null'`)
 ///
 // fn node_value(input: &str) -> IResult<&str, KdlValue, KdlParseError<&str>> {
-pub(crate) fn node_value(input: &str) -> IResult<&str, KdlValue> {
-    // let (input, _ty) = opt(type_annotation)(input)?;
-    alt((
+pub(crate) fn node_value(input: &str) -> IResult<&str, (Option<String>, KdlValue)> {
+    let (input, ty) = opt(type_annotation)(input)?;
+    let (input, value) = alt((
         map(string, KdlValue::String),
-        map(raw_string, |s| KdlValue::String(s.into())),
+        map(raw_string, |s| KdlValue::RawString(s.into())),
         number,
         boolean,
         value(KdlValue::Null, tag("null")),
-    ))(input)
+    ))(input)?;
+    Ok((input, (ty, value)))
+}
+
+/// https://github.com/kdl-org/kdl-rs/blob/v4.7.1/src/parser.rs#L419-L430
+/// `type-annotation := '(' identifier ')'`
+///
+// fn annotation(input: &str) -> IResult<&str, KdlIdentifier, KdlParseError<&str>> {
+fn type_annotation(input: &str) -> IResult<&str, String> {
+    delimited(char('('), identifier, char(')'))(input)
 }
 
 /// https://github.com/kdl-org/kdl-rs/blob/v3.0.0/src/parser.rs#L267-L278
@@ -261,11 +270,11 @@ fn raw_string(input: &str) -> IResult<&str, &str> {
 // fn number(input: &str) -> IResult<&str, KdlValue, KdlParseError<&str>> {
 fn number(input: &str) -> IResult<&str, KdlValue> {
     alt((
-        map(hexadecimal, KdlValue::Int),
-        map(octal, KdlValue::Int),
-        map(binary, KdlValue::Int),
-        map(float, KdlValue::Float),
-        map(integer, KdlValue::Int),
+        map(hexadecimal, KdlValue::Base16),
+        map(octal, KdlValue::Base8),
+        map(binary, KdlValue::Base2),
+        map(float, KdlValue::Base10Float),
+        map(integer, KdlValue::Base10),
     ))(input)
 }
 
@@ -339,8 +348,8 @@ fn binary(input: &str) -> IResult<&str, i64> {
 // fn boolean(input: &str) -> IResult<&str, KdlValue, KdlParseError<&str>> {
 fn boolean(input: &str) -> IResult<&str, KdlValue> {
     alt((
-        value(KdlValue::Boolean(true), tag("true")),
-        value(KdlValue::Boolean(false), tag("false")),
+        value(KdlValue::Bool(true), tag("true")),
+        value(KdlValue::Bool(false), tag("false")),
     ))(input)
 }
 
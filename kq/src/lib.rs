@@ -1,142 +1,230 @@
-use kdl::{KdlNode, KdlValue};
-use nom::combinator::all_consuming;
+use kdl::{KdlDocument, KdlEntry, KdlIdentifier, KdlNode, KdlValue};
+use nom::sequence::terminated;
 use nom::Finish;
-use std::collections::VecDeque;
+use std::borrow::Cow;
+use std::collections::{HashSet, VecDeque};
 use std::iter;
+use std::mem;
 
+mod edit;
+mod error;
 mod evaluation;
 mod kdlrs;
+mod location;
+mod output;
 mod parser;
+mod path;
+mod query;
+mod tree;
 
-use parser::{Accessor, Combinator, Entity, Matcher, Operator, Sibling};
+pub use edit::Edit;
+pub use error::Error;
+pub use location::Location;
+pub use output::Output;
+use parser::{
+    Accessor, Case, Combinator, Entity, Mapping, Matcher, Operator, PseudoClass, Sibling,
+};
+pub use path::Path;
+pub use query::Query;
+use tree::Tree;
 
-pub fn query_document(input: &str, document: Vec<KdlNode>) -> Result<Vec<KdlNode>, String> {
-    let input = input.trim();
-    if input.is_empty() {
-        Ok(document)
+/// Evaluates a selector against `document`, and returns clones of the matched nodes.
+///
+/// With the update operator (`selector |= prop(dev) = false`), the whole document is returned
+/// instead, with the matched nodes updated.
+///
+/// See [`select_document`] to borrow the matched nodes from the document instead.
+pub fn query_document(input: &str, document: Vec<KdlNode>) -> Result<Vec<KdlNode>, Error> {
+    if input.trim().is_empty() {
+        return Ok(document);
+    }
+
+    let (alternation, update) = parse(input, parser::update_query)?;
+    let query = Query {
+        alternation,
+        mapping: None,
+        update,
+    };
+    if query.has_update() {
+        let mut updated = KdlDocument::new();
+        *updated.nodes_mut() = document;
+        query.update(&mut updated);
+        Ok(mem::take(updated.nodes_mut()))
     } else {
-        all_consuming(parser::selector)(input)
-            .finish()
-            .map(|(_input, selector)| query_by_selector(selector, document))
-            .map_err(|error| error.to_string())
+        Ok(query.select(&document).into_iter().cloned().collect())
     }
 }
 
-fn query_by_selector(selector: Vec<Combinator>, document: Vec<KdlNode>) -> Vec<KdlNode> {
+/// Like [`query_document`], but borrows the matched nodes from `document`,
+/// so that neither the nodes nor their children are cloned.
+pub fn select_document<'a>(
+    input: &str,
+    document: &'a [KdlNode],
+) -> Result<Vec<&'a KdlNode>, Error> {
+    if input.trim().is_empty() {
+        Ok(document.iter().collect())
+    } else {
+        parse(input, parser::alternation)
+            .map(|alternation| query_by_alternation(&alternation, &Tree::new(document)))
+    }
+}
+
+/// Like [`query_document`], but also accepts the map operator (`selector => accessor`),
+/// in which case every matched node is projected into the value(s) named by the accessor.
+///
+/// To apply the same query to several documents, parse it once with [`Query::parse`] instead.
+pub fn map_document(input: &str, document: Vec<KdlNode>) -> Result<Vec<Output>, Error> {
+    Query::parse(input).map(|query| query.apply(&document))
+}
+
+/// Runs `parser` over the whole of `input`, and reports errors with their byte offset within `input`.
+fn parse<'a, O>(
+    input: &'a str,
+    parser: impl FnMut(&'a str) -> parser::IResult<'a, O>,
+) -> Result<O, Error> {
+    let trimmed = input.trim_start();
+    terminated(parser, parser::end)(trimmed)
+        .finish()
+        .map(|(_input, output)| output)
+        .map_err(|error| {
+            let offset = input.len() - error.input.len();
+            match error.kind {
+                parser::ErrorKind::Expected(expected) => Error::Selector { offset, expected },
+                parser::ErrorKind::Unsupported(feature) => Error::Unsupported { offset, feature },
+                parser::ErrorKind::Pattern(message) => Error::Pattern { offset, message },
+            }
+        })
+}
+
+/// Evaluates every selector of `a || b || ...` and merges their results in document order,
+/// a node matched by several selectors is only returned once. A single selector is returned
+/// as is, without collecting the positions of the whole document to sort it.
+fn query_by_alternation<'a>(alternation: &[Vec<Combinator>], tree: &Tree<'a>) -> Vec<&'a KdlNode> {
+    match alternation {
+        [selector] => query_by_selector(selector, tree.document(), tree),
+        _ => {
+            let matched = alternation
+                .iter()
+                .flat_map(|selector| query_by_selector(selector, tree.document(), tree));
+            tree.in_document_order(matched)
+        }
+    }
+}
+
+/// Evaluates the combinators of a selector from left to right, each from the nodes matched by
+/// the previous one. The first one starts from the document itself, whose children are the
+/// top-level nodes, and so does a leading `top()`: `top() > a` selects the top-level `a` nodes,
+/// and `top()` on its own all of them. In `:has()`, the "document" is the children of a node.
+fn query_by_selector<'a>(
+    selector: &[Combinator],
+    document: &'a [KdlNode],
+    tree: &Tree<'a>,
+) -> Vec<&'a KdlNode> {
     selector
         .iter()
-        .fold(
-            (&Accessor::Top, document),
-            |(previous, document), combinator| match combinator {
-                Combinator::Child(accessor, siblings) => {
-                    let is_previous_sibling_top = match previous {
-                        Accessor::AnyElement
-                        | Accessor::AnyElementWithTypeTag(_)
-                        | Accessor::Closed(_, _)
-                        | Accessor::Sole(_) => false,
-                        Accessor::Top => true,
-                    };
-                    let document = query_by_child_combinator(
-                        is_previous_sibling_top,
-                        accessor,
-                        siblings,
-                        document,
-                    );
-                    (accessor, document)
-                }
-                Combinator::Descendant(accessor, siblings) => {
-                    let document = query_by_descendant_combinator(accessor, siblings, document);
-                    (accessor, document)
-                }
-            },
-        )
-        .1
+        .fold(None, |matched, combinator| match combinator {
+            // the parser only accepts `top()` at the start, where nothing has been matched yet
+            Combinator::Child(Accessor::Top, _) | Combinator::Descendant(Accessor::Top, _) => {
+                matched
+            }
+            Combinator::Child(accessor, siblings) => Some(query_by_child_combinator(
+                accessor, siblings, matched, document, tree,
+            )),
+            Combinator::Descendant(accessor, siblings) => Some(query_by_descendant_combinator(
+                accessor, siblings, matched, document, tree,
+            )),
+        })
+        .unwrap_or_else(|| document.iter().collect())
 }
 
-fn query_by_child_combinator(
-    is_previous_sibling_top: bool,
+/// `None` for `matched` stands for the document, before the first step of the selector.
+fn query_by_child_combinator<'a>(
     accessor: &Accessor,
     siblings: &[(Sibling, Accessor)],
-    document: Vec<KdlNode>,
-) -> Vec<KdlNode> {
-    if siblings.is_empty() {
-        match accessor {
-            Accessor::AnyElement => {
-                if is_previous_sibling_top {
-                    document
-                } else {
-                    document
-                        .iter()
-                        .flat_map(|node| &node.children)
-                        .cloned()
-                        .collect()
-                }
-            }
-            Accessor::AnyElementWithTypeTag(_identifier) => vec![],
-            Accessor::Closed(identifier, matcher) => identifier
-                .as_ref()
-                .map(|identifier| filter_by_identifier(identifier, &document))
-                .unwrap_or(document)
-                .iter()
-                .filter(|node| match_by_matcher(matcher, node))
-                .cloned()
-                .collect(),
-            Accessor::Sole(identifier) => {
-                if is_previous_sibling_top {
-                    filter_by_identifier(identifier, &document)
-                } else {
-                    document
-                        .iter()
-                        .flat_map(|node| filter_by_identifier(identifier, &node.children))
-                        .collect()
-                }
-            }
-            Accessor::Top => document,
-        }
-    } else if is_previous_sibling_top {
-        filter_by_siblings(accessor, siblings, &document)
-    } else {
-        document
+    matched: Option<Vec<&'a KdlNode>>,
+    document: &'a [KdlNode],
+    tree: &Tree<'a>,
+) -> Vec<&'a KdlNode> {
+    match matched {
+        None => filter_by_combinator(accessor, siblings, document, tree),
+        Some(matched) => matched
             .iter()
-            .flat_map(|node| filter_by_siblings(accessor, siblings, &node.children))
-            .collect()
+            .flat_map(|node| filter_by_combinator(accessor, siblings, children(node), tree))
+            .collect(),
     }
 }
 
-fn query_by_descendant_combinator(
+/// `None` for `matched` stands for the document, before the first step of the selector.
+fn query_by_descendant_combinator<'a>(
     accessor: &Accessor,
     siblings: &[(Sibling, Accessor)],
-    document: Vec<KdlNode>,
-) -> Vec<KdlNode> {
-    if siblings.is_empty() {
-        match accessor {
-            Accessor::AnyElement => document,
-            Accessor::AnyElementWithTypeTag(_identifier) => vec![],
-            Accessor::Closed(identifier, matcher) => traverse(
-                |node| match_by_accessor_filter(identifier, matcher, node),
-                &document,
-            ),
-            Accessor::Sole(identifier) => traverse(|node| node.name == *identifier, &document),
-            Accessor::Top => document,
+    matched: Option<Vec<&'a KdlNode>>,
+    document: &'a [KdlNode],
+    tree: &Tree<'a>,
+) -> Vec<&'a KdlNode> {
+    match (accessor, matched) {
+        (Accessor::Parent | Accessor::Ancestor(_), matched) => {
+            navigate(accessor, &matched.unwrap_or_default(), tree)
+        }
+        (accessor, None) => traverse(accessor, siblings, vec![document], tree),
+        (accessor, Some(matched)) => {
+            let document = matched.iter().map(|node| children(node)).collect();
+            traverse(accessor, siblings, document, tree)
         }
-    } else {
-        traverse_by_siblings(accessor, siblings, &document)
     }
 }
 
-fn filter_by_identifier(identifier: &str, document: &[KdlNode]) -> Vec<KdlNode> {
+/// Goes up from every node to its parent, or to those of its ancestors that match the accessor
+/// of `ancestor()`. A node reached from several nodes is only returned once, in document order.
+fn navigate<'a>(
+    accessor: &Accessor,
+    document: &[&'a KdlNode],
+    tree: &Tree<'a>,
+) -> Vec<&'a KdlNode> {
+    let ancestors = |node| iter::successors(tree.parent(node), |node| tree.parent(node));
+    let matched = document.iter().flat_map(|node| match accessor {
+        Accessor::Ancestor(Some(ancestor)) => ancestors(*node)
+            .filter(|node| match_by_accessor(ancestor, node, tree))
+            .collect(),
+        Accessor::Ancestor(None) => ancestors(*node).collect(),
+        _ => tree.parent(node).into_iter().collect::<Vec<_>>(),
+    });
+    tree.in_document_order(matched)
+}
+
+fn filter_by_accessor<'a>(
+    accessor: &Accessor,
+    document: impl IntoIterator<Item = &'a KdlNode>,
+    tree: &Tree,
+) -> Vec<&'a KdlNode> {
     document
-        .iter()
-        .filter(|node| node.name == *identifier)
-        .cloned()
+        .into_iter()
+        .filter(|node| match_by_accessor(accessor, node, tree))
         .collect()
 }
 
-fn filter_by_siblings(
+/// The nodes among `document`, siblings of each other, that match `accessor` and are preceded
+/// by the `+` and `~` siblings of the combinator.
+fn filter_by_combinator<'a>(
+    accessor: &Accessor,
+    siblings: &[(Sibling, Accessor)],
+    document: &'a [KdlNode],
+    tree: &Tree,
+) -> Vec<&'a KdlNode> {
+    if siblings.is_empty() {
+        filter_by_accessor(accessor, document, tree)
+    } else {
+        let document = document.iter().collect::<Vec<_>>();
+        filter_by_siblings(accessor, siblings, &document, tree)
+    }
+}
+
+fn filter_by_siblings<'a>(
     accessor: &Accessor,
     siblings: &[(Sibling, Accessor)],
-    document: &[KdlNode],
-) -> Vec<KdlNode> {
+    document: &[&'a KdlNode],
+    tree: &Tree,
+) -> Vec<&'a KdlNode> {
     let head = (Sibling::General, accessor.clone());
 
     document
@@ -146,9 +234,9 @@ fn filter_by_siblings(
             let mut siblings = iter::once(&head).chain(siblings.iter()).rev();
             let mut preceding = document[..*i].iter().rev().peekable();
 
-            let result = siblings
-                .next()
-                .and_then(|(sibling, accessor)| match_by_accessor(accessor, node).then(|| sibling));
+            let result = siblings.next().and_then(|(sibling, accessor)| {
+                match_by_accessor(accessor, node, tree).then_some(sibling)
+            });
 
             let result = result.map(|sibling| {
                 let mut previous_sibling = sibling;
@@ -157,9 +245,11 @@ fn filter_by_siblings(
                     let is_sibling_matched = match previous_sibling {
                         Sibling::Adjacent => preceding
                             .next()
-                            .map(|node| match_by_accessor(accessor, node))
+                            .map(|node| match_by_accessor(accessor, node, tree))
                             .unwrap_or(false),
-                        Sibling::General => preceding.any(|node| match_by_accessor(accessor, node)),
+                        Sibling::General => {
+                            preceding.any(|node| match_by_accessor(accessor, node, tree))
+                        }
                     };
                     previous_sibling = sibling;
                     is_sibling_matched
@@ -168,115 +258,280 @@ fn filter_by_siblings(
 
             result.unwrap_or(false)
         })
-        .map(|(_i, node)| node)
-        .cloned()
+        .map(|(_i, node)| *node)
         .collect()
 }
 
+fn match_by_matchers(matchers: &[Matcher], node: &KdlNode) -> bool {
+    matchers
+        .iter()
+        .all(|matcher| match_by_matcher(matcher, node))
+}
+
 fn match_by_matcher(matcher: &Matcher, node: &KdlNode) -> bool {
     match matcher {
         Matcher::Direct(entity) => match entity {
-            Entity::PropName(name) => node.properties.contains_key(name),
-            Entity::Val(index) => node.values.len() > *index,
-            // '[name()]', '[props()]',, and '[values()]' does not make sense by themselves in a matcher
-            // '[tag()]' is unsupported
-            Entity::NodeName | Entity::Props | Entity::TypeTag | Entity::Values => false,
+            Entity::PropName(name) => node.get(name.as_str()).is_some(),
+            Entity::TypeTag => node.ty().is_some(),
+            Entity::Val(index) => node.get(*index).is_some(),
+            Entity::Props => properties(node).next().is_some(),
+            Entity::Values => arguments(node).next().is_some(),
+            // '[name()]' does not make sense by itself in a matcher
+            Entity::NodeName => false,
         },
-        Matcher::Expression(entity, operator, value) => match entity {
+        Matcher::Expression(entity, operator, type_tag, value, case) => match entity {
             Entity::PropName(name) => node
-                .properties
-                .get(name)
-                .map(|lhs| evaluation::evaluate(lhs, operator, value))
+                .get(name.as_str())
+                .map(|lhs| match_by_entry(lhs, operator, type_tag, value, *case))
                 .unwrap_or(false),
             Entity::Val(index) => node
-                .values
                 .get(*index)
-                .map(|lhs| evaluation::evaluate(lhs, operator, value))
+                .map(|lhs| match_by_entry(lhs, operator, type_tag, value, *case))
                 .unwrap_or(false),
-            Entity::NodeName => match value {
-                KdlValue::String(string) => match operator {
-                    Operator::Contains => node.name.contains(string),
-                    Operator::EndsWith => node.name.ends_with(string),
-                    Operator::Equal => &node.name == string,
-                    Operator::GreaterThan => false,
-                    Operator::GreaterThanOrEqualTo => false,
-                    Operator::LessThan => false,
-                    Operator::LessThanOrEqualTo => false,
-                    Operator::NotEqual => &node.name != string,
-                    Operator::StartsWith => node.name.starts_with(string),
-                },
-                KdlValue::Int(_) | KdlValue::Float(_) | KdlValue::Boolean(_) | KdlValue::Null => {
-                    false
-                }
-            },
-            Entity::Props => false,
-            Entity::TypeTag => false,
-            Entity::Values => false,
+            Entity::NodeName => match_by_identifier(node.name(), operator, value, *case),
+            Entity::TypeTag => node
+                .ty()
+                .map(|lhs| match_by_identifier(lhs, operator, value, *case))
+                .unwrap_or(false),
+            Entity::Props => {
+                properties(node).any(|lhs| match_by_entry(lhs, operator, type_tag, value, *case))
+            }
+            Entity::Values => {
+                arguments(node).any(|lhs| match_by_entry(lhs, operator, type_tag, value, *case))
+            }
         },
+        Matcher::Count(entity, operator, type_tag, value) => {
+            let count = match entity {
+                Entity::Props => properties(node).count(),
+                _ => arguments(node).count(),
+            };
+            let count = KdlValue::Base10(count.try_into().unwrap_or(i64::MAX));
+            evaluation::evaluate(
+                &count,
+                operator,
+                type_tag.as_deref(),
+                value,
+                Case::Sensitive,
+            )
+        }
+    }
+}
+
+fn arguments(node: &KdlNode) -> impl Iterator<Item = &KdlEntry> {
+    node.entries().iter().filter(|entry| entry.name().is_none())
+}
+
+fn properties(node: &KdlNode) -> impl Iterator<Item = &KdlEntry> {
+    node.entries().iter().filter(|entry| entry.name().is_some())
+}
+
+/// When the right-hand side carries a type annotation, e.g. `[val() = (date)"2021-01-01"]`,
+/// the entry has to be annotated with the same type as well. `(semver)` is the exception,
+/// it compares the entry as a semantic version whether it is annotated or not.
+fn match_by_entry(
+    entry: &KdlEntry,
+    operator: &Operator,
+    type_tag: &Option<String>,
+    value: &KdlValue,
+    case: Case,
+) -> bool {
+    let type_tag = type_tag.as_deref();
+    let is_annotated = match type_tag {
+        Some(evaluation::SEMVER) | None => true,
+        Some(type_tag) => entry.ty().map(|ty| ty.value() == type_tag).unwrap_or(false),
+    };
+    is_annotated && evaluation::evaluate(entry.value(), operator, type_tag, value, case)
+}
+
+fn match_by_identifier(
+    identifier: &KdlIdentifier,
+    operator: &Operator,
+    value: &KdlValue,
+    case: Case,
+) -> bool {
+    let identifier = identifier.value();
+    match value {
+        KdlValue::RawString(string) | KdlValue::String(string) => {
+            let (identifier, string): (Cow<str>, Cow<str>) = match case {
+                Case::Sensitive => (identifier.into(), string.into()),
+                Case::Insensitive => (
+                    identifier.to_lowercase().into(),
+                    string.to_lowercase().into(),
+                ),
+            };
+            let (identifier, string) = (identifier.as_ref(), string.as_ref());
+            match operator {
+                Operator::Contains => identifier.contains(string),
+                Operator::EndsWith => identifier.ends_with(string),
+                Operator::Equal => identifier == string,
+                Operator::GreaterThan => identifier > string,
+                Operator::GreaterThanOrEqualTo => identifier >= string,
+                Operator::LessThan => identifier < string,
+                Operator::LessThanOrEqualTo => identifier <= string,
+                Operator::Matches(pattern) => pattern.0.is_match(identifier),
+                Operator::NotEqual => identifier != string,
+                Operator::StartsWith => identifier.starts_with(string),
+            }
+        }
+        KdlValue::Base2(_)
+        | KdlValue::Base8(_)
+        | KdlValue::Base10(_)
+        | KdlValue::Base10Float(_)
+        | KdlValue::Base16(_)
+        | KdlValue::Bool(_)
+        | KdlValue::Null => false,
     }
 }
 
-fn match_by_accessor(accessor: &Accessor, node: &KdlNode) -> bool {
+fn match_by_accessor(accessor: &Accessor, node: &KdlNode, tree: &Tree) -> bool {
     match accessor {
         Accessor::AnyElement => true,
-        Accessor::AnyElementWithTypeTag(_identifier) => false,
-        Accessor::Closed(identifier, matcher) => {
-            match_by_accessor_filter(identifier, matcher, node)
+        Accessor::AnyElementWithTypeTag(identifier, case) => {
+            match_by_type_tag(identifier, *case, node)
+        }
+        Accessor::Closed(identifier, matchers, case) => {
+            match_by_accessor_filter(identifier, matchers, *case, node)
+        }
+        Accessor::Sole(identifier, case) => same_identifier(node.name().value(), identifier, *case),
+        Accessor::Pseudo(accessor, pseudo_classes) => {
+            match_by_accessor(accessor, node, tree)
+                && pseudo_classes
+                    .iter()
+                    .all(|pseudo_class| match_by_pseudo_class(pseudo_class, node, tree))
         }
-        Accessor::Sole(identifier) => node.name == *identifier,
-        Accessor::Top => true,
+        // the parser only accepts them as steps of a selector, see `query_by_selector`
+        Accessor::Top | Accessor::Parent | Accessor::Ancestor(_) => false,
+    }
+}
+
+fn match_by_pseudo_class(pseudo_class: &PseudoClass, node: &KdlNode, tree: &Tree) -> bool {
+    match pseudo_class {
+        PseudoClass::Not(accessor) => !match_by_accessor(accessor, node, tree),
+        PseudoClass::Has(selector) => tree.position(node).is_some_and(|position| {
+            !query_by_selector(selector, children(position.node()), tree).is_empty()
+        }),
+        PseudoClass::Empty => children(node).is_empty(),
+        PseudoClass::FirstChild => tree
+            .position(node)
+            .is_some_and(|position| position.index == 0),
+        PseudoClass::LastChild => tree
+            .position(node)
+            .is_some_and(|position| position.index + 1 == position.siblings.len()),
+        PseudoClass::NthChild(nth) => tree
+            .position(node)
+            .is_some_and(|position| nth.matches(position.index + 1)),
+        PseudoClass::NthOfType(nth) => tree.position(node).is_some_and(|position| {
+            let name = node.name().value();
+            let preceding = position.siblings[..position.index]
+                .iter()
+                .filter(|sibling| sibling.name().value() == name)
+                .count();
+            nth.matches(preceding + 1)
+        }),
     }
 }
 
 fn match_by_accessor_filter(
     identifier: &Option<String>,
-    matcher: &Matcher,
+    matchers: &[Matcher],
+    case: Case,
     node: &KdlNode,
 ) -> bool {
     identifier
         .as_ref()
-        .map(|identifier| node.name == *identifier)
+        .map(|identifier| same_identifier(node.name().value(), identifier, case))
         .unwrap_or(true)
-        .then(|| match_by_matcher(matcher, node))
-        .unwrap_or(false)
+        && match_by_matchers(matchers, node)
 }
 
-fn traverse_by_siblings(
-    accessor: &Accessor,
-    siblings: &[(Sibling, Accessor)],
-    document: &[KdlNode],
-) -> Vec<KdlNode> {
-    let mut result = Vec::<KdlNode>::new();
-    let mut queue = VecDeque::<&[KdlNode]>::new();
-    queue.push_back(document);
+/// `()` matches any node with a type annotation, `(identifier)` only that annotation.
+fn match_by_type_tag(identifier: &Option<String>, case: Case, node: &KdlNode) -> bool {
+    match (identifier, node.ty()) {
+        (Some(identifier), Some(ty)) => same_identifier(ty.value(), identifier, case),
+        (None, Some(_ty)) => true,
+        (_, None) => false,
+    }
+}
 
-    while let Some(document) = queue.pop_front() {
-        for node in filter_by_siblings(accessor, siblings, document) {
-            result.push(node);
-        }
-        for node in document {
-            if !node.children.is_empty() {
-                queue.push_back(&node.children);
+/// Compares the name or type annotation of a node with the one written in the selector.
+fn same_identifier(value: &str, identifier: &str, case: Case) -> bool {
+    match case {
+        Case::Sensitive => value == identifier,
+        Case::Insensitive => value.to_lowercase() == identifier.to_lowercase(),
+    }
+}
+
+fn map_by_mapping(mapping: &Mapping, node: &KdlNode) -> Output {
+    match mapping {
+        Mapping::Entity(entity) => map_by_entity(entity, node),
+        Mapping::Tuple(entities) => Output::Tuple(
+            entities
+                .iter()
+                .map(|entity| map_by_entity(entity, node))
+                .collect(),
+        ),
+    }
+}
+
+fn map_by_entity(entity: &Entity, node: &KdlNode) -> Output {
+    match entity {
+        Entity::NodeName => Output::Value(KdlValue::String(node.name().value().to_owned())),
+        Entity::PropName(name) => Output::Value(
+            node.get(name.as_str())
+                .map(|entry| entry.value().clone())
+                .unwrap_or(KdlValue::Null),
+        ),
+        Entity::Props => {
+            let mut properties = vec![];
+            for entry in node.entries() {
+                if let Some(name) = entry.name() {
+                    // only the last of duplicated properties counts
+                    properties.retain(|(key, _value)| key != name.value());
+                    properties.push((name.value().to_owned(), entry.value().clone()));
+                }
             }
+            Output::Map(properties)
+        }
+        Entity::TypeTag => Output::Value(
+            node.ty()
+                .map(|ty| KdlValue::String(ty.value().to_owned()))
+                .unwrap_or(KdlValue::Null),
+        ),
+        Entity::Val(index) => Output::Value(
+            node.get(*index)
+                .map(|entry| entry.value().clone())
+                .unwrap_or(KdlValue::Null),
+        ),
+        Entity::Values => {
+            Output::List(arguments(node).map(|entry| entry.value().clone()).collect())
         }
     }
-
-    result
 }
 
-fn traverse<F>(predicate: F, document: &[KdlNode]) -> Vec<KdlNode>
-where
-    F: Fn(&KdlNode) -> bool,
-{
-    let mut result: Vec<KdlNode> = vec![];
-    let mut queue: VecDeque<&KdlNode> = document.iter().collect();
+/// Applies [`filter_by_combinator`] to every group of siblings in `document` and below,
+/// breadth-first. A group below several of the starting ones is only visited once, e.g. the
+/// children of `b` for `a []` on `a { b { c } }`, which starts from both `a` and `b`.
+fn traverse<'a>(
+    accessor: &Accessor,
+    siblings: &[(Sibling, Accessor)],
+    document: Vec<&'a [KdlNode]>,
+    tree: &Tree,
+) -> Vec<&'a KdlNode> {
+    let mut result = Vec::<&KdlNode>::new();
+    let mut visited = HashSet::<*const KdlNode>::new();
+    let mut queue = VecDeque::from(document);
 
-    while let Some(node) = queue.pop_front() {
-        if predicate(node) {
-            result.push(node.clone());
+    while let Some(document) = queue.pop_front() {
+        if document.is_empty() || !visited.insert(document.as_ptr()) {
+            continue;
         }
-        queue.extend(node.children.iter());
+        result.extend(filter_by_combinator(accessor, siblings, document, tree));
+        queue.extend(document.iter().map(children));
     }
 
     result
 }
+
+fn children(node: &KdlNode) -> &[KdlNode] {
+    node.children().map(KdlDocument::nodes).unwrap_or_default()
+}
@@ -0,0 +1,31 @@
+use kdl::KdlNode;
+
+/// Where a node was written in the source of its document.
+///
+/// Lines and columns start at 1, and columns count characters rather than bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    /// The byte offset of the node, starting at its type annotation or name.
+    pub offset: usize,
+    /// The length of the node in bytes, including its children.
+    pub length: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    /// Locates `node` in `source`, which must be the text its document was parsed from.
+    pub fn of(node: &KdlNode, source: &str) -> Location {
+        let span = node.span();
+        let offset = span.offset();
+        let before = &source[..offset];
+        let start_of_line = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Location {
+            offset,
+            length: span.len(),
+            line: before.matches('\n').count() + 1,
+            column: before[start_of_line..].chars().count() + 1,
+        }
+    }
+}
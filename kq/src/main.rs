@@ -1,36 +1,270 @@
 use std::error;
-use std::io::{self, Read};
+use std::fmt;
+use std::process::ExitCode;
 
 mod cli;
+mod input;
+mod json;
 
-fn main() -> Result<(), Box<dyn error::Error>> {
-    let args = cli::Args::new()?;
+use cli::OutputFormat;
+use input::Input;
 
+/// Exit status with `--exit-status` when no input had a match.
+const EXIT_NO_MATCH: u8 = 1;
+
+fn main() -> ExitCode {
+    let args = match cli::Args::new() {
+        Ok(args) => args,
+        Err(error) => return report(Failure::Usage(error.into())),
+    };
+
+    match run(&args) {
+        Ok(false) if args.exit_status() => ExitCode::from(EXIT_NO_MATCH),
+        Ok(_) => ExitCode::SUCCESS,
+        Err(failure) => report(failure),
+    }
+}
+
+fn report(failure: Failure) -> ExitCode {
+    eprintln!("Error: {}", failure);
+    ExitCode::from(failure.exit_status())
+}
+
+/// Runs the query against every input, or edits them, and tells whether anything matched.
+fn run(args: &cli::Args) -> Result<bool, Failure> {
     if args.help() {
         args.print_help();
-        return Ok(());
+        return Ok(true);
     }
 
     if args.version() {
         args.print_version();
-        return Ok(());
+        return Ok(true);
     }
 
-    let query = match args.get_query() {
-        Some(query) => query,
+    let (selector, query) = match args.get_query() {
+        Some(selector) => (
+            selector,
+            kq::Query::parse(selector)
+                .map_err(|error| Failure::Selector(error, selector.clone()))?,
+        ),
         None => {
             args.print_help();
-            return Ok(());
+            return Ok(true);
         }
     };
+    let query = if args.ignore_case() {
+        query.ignore_case()
+    } else {
+        query
+    };
+
+    let format = args
+        .get_output_format()
+        .map_err(|error| Failure::Usage(error.into()))?;
+    if args.with_filename() && !matches!(format, OutputFormat::Kdl) {
+        let error = "--with-filename can only be used with the kdl output format";
+        return Err(Failure::Usage(error.into()));
+    }
+    if args.with_location() && !matches!(format, OutputFormat::Kdl) {
+        let error = "--with-location can only be used with the kdl output format";
+        return Err(Failure::Usage(error.into()));
+    }
+    if args.paths() && query.has_mapping() {
+        let error = "--paths can not be combined with the map operator `=>`";
+        return Err(Failure::Usage(error.into()));
+    }
+    let edit = args
+        .get_edit()
+        .map_err(|error| Failure::Usage(error.into()))?;
+    if edit.is_some() && query.has_update() {
+        let error = "the update operator `|=` can not be combined with --set, --delete or --rename";
+        return Err(Failure::Usage(error.into()));
+    }
+    let editing = edit.is_some() || query.has_update();
+    if editing {
+        // edits print the whole edited document, which none of these apply to
+        let options = [
+            (
+                !matches!(format, OutputFormat::Kdl),
+                "--output json or jsonl",
+            ),
+            (args.paths(), "--paths"),
+            (args.with_filename(), "--with-filename"),
+            (args.with_location(), "--with-location"),
+        ];
+        if let Some((_, option)) = options.iter().find(|(given, _)| *given) {
+            let error = format!(
+                "{} can not be combined with --set, --delete, --rename or the update operator `|=`",
+                option
+            );
+            return Err(Failure::Usage(error.into()));
+        }
+    }
+    let inputs = Input::expand(args.get_files()).map_err(Failure::Input)?;
+    if args.in_place() {
+        if !editing {
+            let error = "--in-place requires --set, --delete, --rename or the update operator `|=`";
+            return Err(Failure::Usage(error.into()));
+        }
+        if inputs.iter().any(|input| matches!(input, Input::Stdin)) {
+            let error = "--in-place can only edit files, not the standard input";
+            return Err(Failure::Usage(error.into()));
+        }
+    }
+    let mut matched = false;
+    let mut results = vec![];
+
+    for input in inputs {
+        let buffer = input.read_to_string().map_err(Failure::Input)?;
+        let mut document = buffer
+            .parse::<kdl::KdlDocument>()
+            .map_err(|error| Failure::Document(error.into(), input.name(), buffer.clone()))?;
 
-    let mut buffer = String::new();
-    let mut stdin = io::stdin();
-    stdin.read_to_string(&mut buffer)?;
+        if editing {
+            let count = match &edit {
+                Some(edit) => query
+                    .edit(&mut document, edit)
+                    .map_err(|error| Failure::Selector(error, selector.clone()))?,
+                None => query.update(&mut document),
+            };
+            matched |= count > 0;
 
-    let nodes = kdl::parse_document(buffer)?;
-    let nodes = kq::query_document(query, nodes)?;
-    nodes.iter().for_each(|node| println!("{}", node));
+            if args.in_place() {
+                input.write(&document.to_string()).map_err(Failure::Input)?;
+            } else {
+                print!("{}", document);
+            }
+            continue;
+        }
+
+        let found = if args.paths() {
+            query
+                .select_with_paths(document.nodes())
+                .into_iter()
+                .map(|(path, node)| (node, Found::Path(path)))
+                .collect::<Vec<_>>()
+        } else {
+            query
+                .select(document.nodes())
+                .into_iter()
+                .map(|node| (node, Found::Output(query.project(node))))
+                .collect()
+        };
+
+        matched |= !found.is_empty();
+
+        for (node, found) in found {
+            match format {
+                OutputFormat::Kdl if args.with_location() => {
+                    let location = kq::Location::of(node, &buffer);
+                    print!("{}:{}:{}: ", input.name(), location.line, location.column);
+                    println!("{}", found);
+                }
+                OutputFormat::Kdl if args.with_filename() => {
+                    let name = input.name();
+                    found
+                        .to_string()
+                        .lines()
+                        .for_each(|line| println!("{}:{}", name, line));
+                }
+                OutputFormat::Kdl => println!("{}", found),
+                OutputFormat::Json => results.push(found.to_json()),
+                OutputFormat::JsonLines => println!("{}", found.to_json()),
+            }
+        }
+    }
+
+    if let OutputFormat::Json = format {
+        println!("{:#}", serde_json::Value::Array(results));
+    }
+
+    Ok(matched)
+}
+
+/// What is printed for every match, the match itself or where it is in the document.
+#[allow(clippy::large_enum_variant)]
+enum Found {
+    Output(kq::Output),
+    Path(kq::Path),
+}
+
+impl Found {
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Found::Output(output) => json::from_output(output),
+            Found::Path(path) => path.to_string().into(),
+        }
+    }
+}
+
+impl fmt::Display for Found {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Found::Output(output) => write!(f, "{}", output),
+            Found::Path(path) => write!(f, "{}", path),
+        }
+    }
+}
+
+/// The reasons kq can fail, each with its own exit status so that scripts can tell them apart.
+enum Failure {
+    /// Invalid command line options.
+    Usage(Box<dyn error::Error>),
+    /// An input that cannot be found or read.
+    Input(input::Error),
+    /// A selector that cannot be parsed or used for an edit, along with the selector.
+    Selector(kq::Error, String),
+    /// An input that is not a valid KDL document, along with its name and content.
+    Document(kq::Error, String, String),
+}
+
+impl Failure {
+    fn exit_status(&self) -> u8 {
+        match self {
+            Failure::Usage(_) | Failure::Input(_) => 2,
+            Failure::Selector(..) => 3,
+            Failure::Document(..) => 4,
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Usage(error) => write!(f, "{}", error),
+            Failure::Input(error) => write!(f, "{}", error),
+            Failure::Selector(error, selector) => {
+                writeln!(f, "{}", error)?;
+                write_caret(f, selector, error.offset())
+            }
+            Failure::Document(error, name, document) => {
+                let offset = error.offset();
+                let start = document[..offset].rfind('\n').map_or(0, |i| i + 1);
+                let end = document[offset..]
+                    .find('\n')
+                    .map_or(document.len(), |i| offset + i);
+                let line = document[..start].lines().count() + 1;
+                let column = document[start..offset].chars().count() + 1;
+
+                writeln!(f, "{}:{}:{}: {}", name, line, column, error)?;
+                write_caret(
+                    f,
+                    document[start..end].trim_end_matches('\r'),
+                    offset - start,
+                )
+            }
+        }
+    }
+}
 
-    Ok(())
+/// Writes `line`, and a caret under the character at byte `offset` on the next line.
+fn write_caret(f: &mut fmt::Formatter<'_>, line: &str, offset: usize) -> fmt::Result {
+    // tabs are kept so that the caret lines up with what the terminal shows
+    let padding = line[..offset]
+        .chars()
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    writeln!(f, "    {}", line)?;
+    write!(f, "    {}^", padding)
 }
@@ -0,0 +1,58 @@
+use kdl::{KdlIdentifier, KdlNode, KdlValue};
+use std::fmt;
+
+/// A single result of a query.
+///
+/// A plain selector yields whole nodes, while the map operator (`selector => accessor`)
+/// projects every matched node into a value, a list of values, a map of properties,
+/// or a tuple of those.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Output {
+    Node(KdlNode),
+    Value(KdlValue),
+    List(Vec<KdlValue>),
+    /// Properties in the order they are written, where only the last of duplicated ones counts.
+    Map(Vec<(String, KdlValue)>),
+    Tuple(Vec<Output>),
+}
+
+/// Outputs are printed the same way their parts would appear in a KDL node:
+/// values and list items are separated by a space, and map entries are written as `key=value`.
+/// Nodes are printed without the whitespace and comments they had in the document.
+impl fmt::Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Output::Node(node) => {
+                let mut node = node.clone();
+                node.clear_fmt_recursive();
+                write!(f, "{}", node)
+            }
+            Output::Value(value) => write!(f, "{}", value),
+            Output::List(values) => write_separated(f, values.iter()),
+            Output::Map(properties) => {
+                for (i, (key, value)) in properties.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}={}", KdlIdentifier::from(key.as_str()), value)?;
+                }
+                Ok(())
+            }
+            Output::Tuple(outputs) => write_separated(f, outputs.iter()),
+        }
+    }
+}
+
+fn write_separated<T: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    items: impl Iterator<Item = T>,
+) -> fmt::Result {
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            write!(f, " ")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
@@ -1,21 +1,37 @@
-use kdl::KdlValue;
+use kdl::{KdlDocument, KdlEntry, KdlValue};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::digit0;
-use nom::combinator::{iterator, map, opt, value};
-use nom::multi::many1;
-use nom::sequence::{delimited, tuple};
-use nom::IResult;
+use nom::character::complete::{digit0, digit1, one_of};
+use nom::combinator::{consumed, cut, eof, iterator, map, not, opt, success, value, verify};
+use nom::error::ParseError;
+use nom::multi::{many0, many1};
+use nom::sequence::{delimited, preceded, terminated, tuple};
+use regex::{Regex, RegexBuilder};
+use std::cmp::Ordering;
 use std::convert::TryFrom;
+use std::iter;
 
+use crate::edit::{self, Edit};
 use crate::kdlrs;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub(crate) enum Combinator {
     Child(Accessor, Vec<(Sibling, Accessor)>),
     Descendant(Accessor, Vec<(Sibling, Accessor)>),
 }
 
+impl Combinator {
+    /// Makes every matcher of the combinator compare strings regardless of case.
+    pub(crate) fn ignore_case(&mut self) {
+        let (Combinator::Child(accessor, siblings) | Combinator::Descendant(accessor, siblings)) =
+            self;
+        accessor.ignore_case();
+        for (_sibling, accessor) in siblings {
+            accessor.ignore_case();
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) enum Sibling {
     Adjacent,
@@ -38,16 +54,132 @@ impl TryFrom<&ParsedCombinator> for Sibling {
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) enum Accessor {
     AnyElement,
-    AnyElementWithTypeTag(Option<String>),
-    Closed(Option<String>, Matcher),
-    Sole(String),
+    /// `()` or `(tag)`, with whether the type annotation is compared regardless of case.
+    AnyElementWithTypeTag(Option<String>, Case),
+    /// `name[matcher]` or `[matcher]`, with whether the name is compared regardless of case.
+    Closed(Option<String>, Vec<Matcher>, Case),
+    /// A bare name, with whether it is compared regardless of case.
+    Sole(String, Case),
     Top,
+    /// An accessor narrowed down by pseudo-classes, e.g. `dependencies:not([platform])`.
+    Pseudo(Box<Accessor>, Vec<PseudoClass>),
+    /// `parent()`, a step from every node up to its parent, e.g. `winapi parent()`.
+    Parent,
+    /// `ancestor(accessor)`, a step from every node up to those of its ancestors that match
+    /// the accessor, or to all of them with `ancestor()`.
+    Ancestor(Option<Box<Accessor>>),
+}
+
+impl Accessor {
+    /// Makes the accessor compare names, type annotations and the strings of its matchers
+    /// regardless of case.
+    pub(crate) fn ignore_case(&mut self) {
+        match self {
+            Accessor::AnyElementWithTypeTag(_, case) | Accessor::Sole(_, case) => {
+                *case = Case::Insensitive
+            }
+            Accessor::Closed(_, matchers, case) => {
+                matchers.iter_mut().for_each(Matcher::ignore_case);
+                *case = Case::Insensitive;
+            }
+            Accessor::Ancestor(Some(accessor)) => accessor.ignore_case(),
+            Accessor::Pseudo(accessor, pseudo_classes) => {
+                accessor.ignore_case();
+                for pseudo_class in pseudo_classes {
+                    match pseudo_class {
+                        PseudoClass::Not(accessor) => accessor.ignore_case(),
+                        PseudoClass::Has(selector) => {
+                            selector.iter_mut().for_each(Combinator::ignore_case)
+                        }
+                        PseudoClass::Empty
+                        | PseudoClass::FirstChild
+                        | PseudoClass::LastChild
+                        | PseudoClass::NthChild(_)
+                        | PseudoClass::NthOfType(_) => {}
+                    }
+                }
+            }
+            Accessor::AnyElement | Accessor::Top | Accessor::Parent | Accessor::Ancestor(None) => {}
+        }
+    }
+
+    /// How `top()`, `parent()` and `ancestor()` are called in errors. They say where a selector
+    /// goes rather than what a node looks like, so they can only be steps of a selector: `top()`
+    /// the first one, `parent()` and `ancestor()` any other one after whitespace.
+    fn step(&self) -> Option<&'static str> {
+        match self {
+            Accessor::Top => Some("`top()`"),
+            Accessor::Parent => Some("`parent()`"),
+            Accessor::Ancestor(_) => Some("`ancestor()`"),
+            Accessor::AnyElement
+            | Accessor::AnyElementWithTypeTag(_, _)
+            | Accessor::Closed(_, _, _)
+            | Accessor::Sole(_, _)
+            | Accessor::Pseudo(_, _) => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum PseudoClass {
+    /// `:not(accessor)`, the node must not match the accessor.
+    Not(Accessor),
+    /// `:has(selector)`, the selector must match somewhere among the descendants of the node,
+    /// or among its children when it starts with `>`.
+    Has(Vec<Combinator>),
+    /// `:empty`, the node has no children.
+    Empty,
+    FirstChild,
+    LastChild,
+    /// `:nth-child(an+b)`, counting all the siblings of the node.
+    NthChild(Nth),
+    /// `:nth-of-type(an+b)`, counting only the siblings with the same name as the node.
+    NthOfType(Nth),
+}
+
+/// The `an+b` argument of `:nth-child()`, which matches the positions `a*n+b` for any `n >= 0`,
+/// counting from 1, e.g. `2n+1` matches the first, third, fifth and so on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Nth {
+    pub(crate) step: i64,
+    pub(crate) offset: i64,
+}
+
+impl Nth {
+    pub(crate) fn matches(&self, position: usize) -> bool {
+        let distance = position as i128 - i128::from(self.offset);
+        match i128::from(self.step) {
+            0 => distance == 0,
+            step => distance % step == 0 && distance / step >= 0,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Matcher {
     Direct(Entity),
-    Expression(Entity, Operator, KdlValue),
+    Expression(Entity, Operator, Option<String>, KdlValue, Case),
+    /// Compares the number of arguments or properties, e.g. `[count(values()) >= 2]`.
+    Count(Entity, Operator, Option<String>, KdlValue),
+}
+
+impl Matcher {
+    /// Makes the matcher compare strings regardless of case, as the `i` modifier does.
+    pub(crate) fn ignore_case(&mut self) {
+        if let Matcher::Expression(_, operator, _, _, case) = self {
+            if let Operator::Matches(pattern) = operator {
+                *pattern = pattern.ignore_case();
+            }
+            *case = Case::Insensitive;
+        }
+    }
+}
+
+/// Whether a matcher tells strings apart by case, `[name() = "windows" i]` does not.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Case {
+    Sensitive,
+    Insensitive,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -69,10 +201,49 @@ pub enum Operator {
     GreaterThanOrEqualTo,
     LessThan,
     LessThanOrEqualTo,
+    Matches(Pattern),
     NotEqual,
     StartsWith,
 }
 
+/// The regular expression of `~=`, compiled once when the query is parsed.
+#[derive(Debug, Clone)]
+pub struct Pattern(pub(crate) Regex);
+
+impl Pattern {
+    fn ignore_case(&self) -> Pattern {
+        let regex = RegexBuilder::new(self.0.as_str())
+            .case_insensitive(true)
+            .build()
+            .unwrap_or_else(|_| self.0.clone());
+        Pattern(regex)
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Mapping {
+    Entity(Entity),
+    Tuple(Vec<Entity>),
+}
+
+/// The assignments of the update operator, applied in order to every matched node.
+pub(crate) type Update = Vec<(Entity, Edit)>;
+
+/// What follows the selector of a query.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Clause<'a> {
+    /// `=> mapping`, along with the input it starts at, to tell where it is in the query.
+    Mapping(&'a str, Mapping),
+    /// `|= update`
+    Update(Update),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 enum ParsedCombinator {
     AdjacentSibling,
@@ -81,11 +252,227 @@ enum ParsedCombinator {
     GeneralSibling,
 }
 
-pub(crate) fn selector(input: &str) -> IResult<&str, Vec<Combinator>> {
-    let (input, head) = accessor(input)?;
-    let mut it = iterator(input, tuple((combinator, accessor)));
+/// Where parsing failed, and what was expected there.
+///
+/// When alternatives fail, the one that got furthest into the input is kept, so that
+/// an error points at the offending token rather than at the start of the accessor.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Error<'a> {
+    pub(crate) input: &'a str,
+    pub(crate) kind: ErrorKind,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum ErrorKind {
+    Expected(Vec<&'static str>),
+    Unsupported(String),
+    Pattern(String),
+}
+
+impl<'a> Error<'a> {
+    fn expected(input: &'a str, expected: &'static str) -> Self {
+        Error {
+            input,
+            kind: ErrorKind::Expected(vec![expected]),
+        }
+    }
+}
+
+impl<'a> ParseError<&'a str> for Error<'a> {
+    fn from_error_kind(input: &'a str, _kind: nom::error::ErrorKind) -> Self {
+        Error {
+            input,
+            kind: ErrorKind::Expected(vec![]),
+        }
+    }
+
+    fn append(_input: &'a str, _kind: nom::error::ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn or(self, other: Self) -> Self {
+        match self.input.len().cmp(&other.input.len()) {
+            Ordering::Less => self,
+            Ordering::Greater => other,
+            Ordering::Equal => match (self.kind, other.kind) {
+                (ErrorKind::Expected(mut expected), ErrorKind::Expected(other_expected)) => {
+                    for token in other_expected {
+                        if !expected.contains(&token) {
+                            expected.push(token);
+                        }
+                    }
+                    Error {
+                        input: self.input,
+                        kind: ErrorKind::Expected(expected),
+                    }
+                }
+                (kind @ (ErrorKind::Unsupported(_) | ErrorKind::Pattern(_)), _)
+                | (_, kind @ (ErrorKind::Unsupported(_) | ErrorKind::Pattern(_))) => Error {
+                    input: self.input,
+                    kind,
+                },
+            },
+        }
+    }
+}
+
+pub(crate) type IResult<'a, O> = nom::IResult<&'a str, O, Error<'a>>;
+
+/// Names what `parser` expects, when it fails without consuming any input.
+fn expect<'a, O>(
+    expected: &'static str,
+    mut parser: impl FnMut(&'a str) -> IResult<'a, O>,
+) -> impl FnMut(&'a str) -> IResult<'a, O> {
+    move |input| {
+        parser(input).map_err(|error| {
+            error.map(|error| match error.kind {
+                ErrorKind::Expected(_) if error.input.len() == input.len() => {
+                    Error::expected(input, expected)
+                }
+                _ => error,
+            })
+        })
+    }
+}
+
+/// Fails for good at `input`, which is valid syntax, but uses a feature that kq does not support.
+fn unsupported(input: &str, feature: String) -> nom::Err<Error<'_>> {
+    nom::Err::Failure(Error {
+        input,
+        kind: ErrorKind::Unsupported(feature),
+    })
+}
+
+/// Adapts a parser of the vendored KDL grammar, which fails with nom's default error.
+fn kdl<'a, O>(
+    expected: &'static str,
+    mut parser: impl FnMut(&'a str) -> nom::IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<'a, O> {
+    move |input| parser(input).map_err(|error| error.map(|_| Error::expected(input, expected)))
+}
+
+fn identifier(input: &str) -> IResult<'_, String> {
+    kdl("an identifier", kdlrs::identifier)(input)
+}
+
+fn whitespace(input: &str) -> IResult<'_, ()> {
+    kdl("whitespace", kdlrs::whitespace)(input)
+}
+
+fn node_value(input: &str) -> IResult<'_, (Option<String>, KdlValue)> {
+    kdl("a value", kdlrs::node_value)(input)
+}
+
+/// Matches the end of the input, after any trailing whitespace.
+pub(crate) fn end(input: &str) -> IResult<'_, ()> {
+    let (input, _) = many0(whitespace)(input)?;
+    let (input, _) = expect("the end of the selector", eof)(input)?;
+    Ok((input, ()))
+}
+
+/// `query := alternation (ws+ '=>' ws+ mapping | ws+ '|=' ws+ update)?`
+pub(crate) fn query(input: &str) -> IResult<'_, (Vec<Vec<Combinator>>, Option<Clause<'_>>)> {
+    tuple((
+        alternation,
+        opt(alt((
+            preceded(
+                tuple((many1(whitespace), tag("=>"))),
+                cut(preceded(many1(whitespace), |input| {
+                    let (rest, mapping) = mapping(input)?;
+                    Ok((rest, Clause::Mapping(input, mapping)))
+                })),
+            ),
+            map(update_clause, Clause::Update),
+        ))),
+    ))(input)
+}
+
+/// `update-query := alternation (ws+ '|=' ws+ update)?`
+pub(crate) fn update_query(input: &str) -> IResult<'_, (Vec<Vec<Combinator>>, Update)> {
+    tuple((
+        alternation,
+        map(opt(update_clause), Option::unwrap_or_default),
+    ))(input)
+}
+
+fn update_clause(input: &str) -> IResult<'_, Update> {
+    preceded(
+        tuple((many1(whitespace), tag("|="))),
+        cut(preceded(many1(whitespace), update)),
+    )(input)
+}
+
+/// `alternation := selector (ws* '||' ws* selector)*`
+pub(crate) fn alternation(input: &str) -> IResult<'_, Vec<Vec<Combinator>>> {
+    let (input, head) = selector(input)?;
+    let (input, tail) = many0(preceded(
+        delimited(many0(whitespace), tag("||"), many0(whitespace)),
+        cut(selector),
+    ))(input)?;
+
+    Ok((input, iter::once(head).chain(tail).collect()))
+}
+
+pub(crate) fn selector(input: &str) -> IResult<'_, Vec<Combinator>> {
+    let (rest, head) = accessor(input)?;
+    let is_top = head == Accessor::Top;
+    match head.step() {
+        Some(step) if !is_top => {
+            return Err(unsupported(
+                input,
+                format!("{} at the start of a selector", step),
+            ));
+        }
+        Some(step) => siblings_after(rest, step)?,
+        None => {}
+    }
+    let input = rest;
+    let mut previous_is_top = is_top;
+    let mut it = iterator(input, |input| {
+        // `||` and `|` are valid bare identifiers, but here they separate alternatives or start
+        // an update
+        let mut accessor = expect(
+            "an accessor",
+            preceded(not(alt((tag("||"), tag("|=")))), accessor),
+        );
+        let (input, combinator) = combinator(input)?;
+        let symbol = match combinator {
+            ParsedCombinator::AdjacentSibling => Some("+"),
+            ParsedCombinator::Child => Some(">"),
+            ParsedCombinator::Descendant => None,
+            ParsedCombinator::GeneralSibling => Some("~"),
+        };
+        let (rest, accessor) = match combinator {
+            ParsedCombinator::AdjacentSibling => {
+                cut(expect("a selector after `+`", accessor))(input)?
+            }
+            ParsedCombinator::Child => cut(expect("a selector after `>`", accessor))(input)?,
+            // a descendant combinator is only whitespace, which may as well precede `||` or `=>`
+            ParsedCombinator::Descendant => accessor(input)?,
+            ParsedCombinator::GeneralSibling => {
+                cut(expect("a selector after `~`", accessor))(input)?
+            }
+        };
+        if accessor == Accessor::Top {
+            let feature = "`top()` in the middle of a selector".to_owned();
+            return Err(unsupported(input, feature));
+        }
+        if let Some(step) = accessor.step() {
+            if let Some(symbol) = symbol {
+                let feature = format!("{} after `{}`", step, symbol);
+                return Err(unsupported(input, feature));
+            }
+            // there is nothing above the document to step up to
+            if previous_is_top {
+                return Err(unsupported(input, format!("{} after `top()`", step)));
+            }
+            siblings_after(rest, step)?;
+        }
+        previous_is_top = false;
+        Ok((rest, (combinator, accessor)))
+    });
     let tail = it.collect::<Vec<(ParsedCombinator, Accessor)>>();
-    it.finish()?;
+    let (input, ()) = it.finish()?;
 
     let mut output = vec![Combinator::Descendant(head, vec![])];
     let mut iter = tail.iter();
@@ -117,7 +504,19 @@ pub(crate) fn selector(input: &str) -> IResult<&str, Vec<Combinator>> {
         }
     }
 
-    Ok(("", output))
+    Ok((input, output))
+}
+
+/// Rejects `+` and `~` after `step`, as the document of `top()` has no siblings, and those of a
+/// step up would be the siblings of the nodes it started from.
+fn siblings_after<'a>(input: &'a str, step: &str) -> Result<(), nom::Err<Error<'a>>> {
+    if let Ok((sibling, _)) = many1(whitespace)(input) {
+        if terminated(one_of("+~"), many1(whitespace))(sibling).is_ok() {
+            let feature = format!("`{}` after {}", &sibling[..1], step);
+            return Err(unsupported(sibling, feature));
+        }
+    }
+    Ok(())
 }
 
 fn is_sibling(value: Option<&(ParsedCombinator, Accessor)>) -> bool {
@@ -130,58 +529,311 @@ fn is_sibling(value: Option<&(ParsedCombinator, Accessor)>) -> bool {
     }
 }
 
-fn combinator(input: &str) -> IResult<&str, ParsedCombinator> {
+fn combinator(input: &str) -> IResult<'_, ParsedCombinator> {
     alt((
-        delimited(
-            many1(kdlrs::whitespace),
+        preceded(
+            many1(whitespace),
             alt((
-                value(ParsedCombinator::Child, tag(">")),
-                value(ParsedCombinator::AdjacentSibling, tag("+")),
-                value(ParsedCombinator::GeneralSibling, tag("~")),
+                // unlike `+` and `~`, `>` can not start an identifier
+                value(
+                    ParsedCombinator::Child,
+                    terminated(tag(">"), cut(after_child_combinator)),
+                ),
+                value(
+                    ParsedCombinator::AdjacentSibling,
+                    terminated(tag("+"), many1(whitespace)),
+                ),
+                value(
+                    ParsedCombinator::GeneralSibling,
+                    terminated(tag("~"), many1(whitespace)),
+                ),
             )),
-            many1(kdlrs::whitespace),
         ),
-        value(ParsedCombinator::Descendant, many1(kdlrs::whitespace)),
+        value(ParsedCombinator::Descendant, many1(whitespace)),
     ))(input)
 }
 
+/// The whitespace after `>`, which a selector that stops short, e.g. `a >`, may lack, so that
+/// it is reported as a missing selector rather than as missing whitespace.
+fn after_child_combinator(input: &str) -> IResult<'_, ()> {
+    if input.is_empty() || input.starts_with(')') {
+        return Ok((input, ()));
+    }
+    value((), many1(whitespace))(input)
+}
+
 /// ```text
 /// accessor :=
 ///   'top()' |
+///   simple-accessor pseudo-class* |
+///   pseudo-class+
+///
+/// simple-accessor :=
 ///   '[]' |
 ///   '(' identifier? ')' |
-///   identifier? matcher |
-///   identifier
+///   name? matcher+ |
+///   name
 /// ```
-fn accessor(input: &str) -> IResult<&str, Accessor> {
-    alt((
-        value(Accessor::Top, tag("top()")),
-        value(Accessor::AnyElement, tag("[]")),
-        map(
-            delimited(tag("("), opt(kdlrs::identifier), tag(")")),
-            Accessor::AnyElementWithTypeTag,
-        ),
-        map(
-            tuple((opt(kdlrs::identifier), matcher)),
-            |(identifier, matcher)| Accessor::Closed(identifier, matcher),
-        ),
-        map(kdlrs::identifier, Accessor::Sole),
-    ))(input)
+fn accessor(input: &str) -> IResult<'_, Accessor> {
+    let (input, accessor) = expect(
+        "an accessor",
+        alt((
+            value(Accessor::Top, tag("top()")),
+            value(Accessor::Parent, tag("parent()")),
+            ancestor,
+            value(Accessor::AnyElement, tag("[]")),
+            // like `[]:first-child`, which `:` can not start the name of
+            value(
+                Accessor::AnyElement,
+                verify(success(()), |_| is_pseudo_class(input)),
+            ),
+            map(
+                delimited(tag("("), opt(identifier), cut(expect("`)`", tag(")")))),
+                |identifier| Accessor::AnyElementWithTypeTag(identifier, Case::Sensitive),
+            ),
+            map(
+                tuple((opt(name), many1(matcher))),
+                |(identifier, matchers)| Accessor::Closed(identifier, matchers, Case::Sensitive),
+            ),
+            map(name, |name| Accessor::Sole(name, Case::Sensitive)),
+        )),
+    )(input)?;
+    let (rest, pseudo_classes) = many0(pseudo_class)(input)?;
+
+    match (accessor, pseudo_classes.is_empty()) {
+        (accessor, true) => Ok((rest, accessor)),
+        (accessor, false) if accessor.step().is_some() => {
+            let feature = format!("a pseudo-class on {}", accessor.step().unwrap_or_default());
+            Err(unsupported(input, feature))
+        }
+        (accessor, false) => Ok((rest, Accessor::Pseudo(Box::new(accessor), pseudo_classes))),
+    }
+}
+
+/// `ancestor := 'ancestor(' ws* accessor? ws* ')'`
+fn ancestor(input: &str) -> IResult<'_, Accessor> {
+    let (input, _) = tuple((tag("ancestor("), many0(whitespace)))(input)?;
+    let (rest, ancestor) = cut(opt(accessor))(input)?;
+    if let Some(step) = ancestor.as_ref().and_then(Accessor::step) {
+        let feature = format!("{} in `ancestor()`", step);
+        return Err(unsupported(input, feature));
+    }
+    let (rest, _) = cut(tuple((many0(whitespace), expect("`)`", tag(")")))))(rest)?;
+    Ok((rest, Accessor::Ancestor(ancestor.map(Box::new))))
+}
+
+/// The identifier of an accessor, which ends before a pseudo-class although `:` is a valid
+/// character of bare identifiers, e.g. `dependencies` in `dependencies:not([platform])`.
+fn name(input: &str) -> IResult<'_, String> {
+    let (rest, identifier) = identifier(input)?;
+    let consumed = &input[..input.len() - rest.len()];
+    let is_bare = consumed == identifier;
+    let pseudo_class = consumed
+        .match_indices(':')
+        .map(|(index, _)| index)
+        .find(|index| is_bare && *index > 0 && is_pseudo_class(&input[*index..]));
+    match pseudo_class {
+        Some(index) => {
+            let (_, identifier) = identifier_in(&input[..index])?;
+            Ok((&input[index..], identifier))
+        }
+        None => Ok((rest, identifier)),
+    }
+}
+
+fn identifier_in(input: &str) -> IResult<'_, String> {
+    terminated(identifier, eof)(input)
 }
 
-/// `matcher := '[' entity (ws+ operator ws+ kdl-value)? ']'`
-fn matcher(input: &str) -> IResult<&str, Matcher> {
+/// The pseudo-classes, those with an argument up to their `(`.
+const PSEUDO_CLASSES: [&str; 7] = [
+    "not(",
+    "has(",
+    "nth-child(",
+    "nth-of-type(",
+    "first-child",
+    "last-child",
+    "empty",
+];
+
+fn is_pseudo_class(input: &str) -> bool {
+    let is_boundary = |rest: &str| {
+        rest.chars()
+            .next()
+            .is_none_or(|c| c == ':' || c.is_whitespace() || r#"\/(){}<>;[]=,""#.contains(c))
+    };
+    PSEUDO_CLASSES.iter().any(|name| {
+        input
+            .strip_prefix(':')
+            .and_then(|input| input.strip_prefix(name))
+            .is_some_and(|rest| name.ends_with('(') || is_boundary(rest))
+    })
+}
+
+/// ```text
+/// pseudo-class :=
+///   ':not(' ws* accessor ws* ')' |
+///   ':has(' ws* ('>' ws+)? selector ws* ')' |
+///   ':nth-child(' ws* nth ws* ')' |
+///   ':nth-of-type(' ws* nth ws* ')' |
+///   ':first-child' |
+///   ':last-child' |
+///   ':empty'
+/// ```
+///
+/// Other pseudo-classes of CSS selectors, such as `[]:hover`, are not part of KQL.
+fn pseudo_class(input: &str) -> IResult<'_, PseudoClass> {
+    let (rest, name) = preceded(tag(":"), name)(input)?;
+    match name.as_str() {
+        "has" => {
+            let (rest, _) = tuple((cut(expect("`(`", tag("("))), many0(whitespace)))(rest)?;
+            // `+ a` and `~ a` would look at the siblings of the node, not at its descendants
+            if terminated(one_of("+~"), many1(whitespace))(rest).is_ok() {
+                let feature = "a sibling combinator in `:has()`".to_owned();
+                return Err(unsupported(rest, feature));
+            }
+            let (argument, child) = opt(terminated(tag(">"), many1(whitespace)))(rest)?;
+            let (rest, mut selector) = cut(selector)(argument)?;
+            // the selector starts from the children of the node, not from the document
+            if let Some(Combinator::Descendant(Accessor::Top, _)) = selector.first() {
+                let feature = "`top()` in `:has()`".to_owned();
+                return Err(unsupported(argument, feature));
+            }
+            let (rest, _) = cut(tuple((many0(whitespace), expect("`)`", tag(")")))))(rest)?;
+            if child.is_some() {
+                if let Combinator::Descendant(accessor, siblings) = selector.remove(0) {
+                    selector.insert(0, Combinator::Child(accessor, siblings));
+                }
+            }
+            Ok((rest, PseudoClass::Has(selector)))
+        }
+        "empty" => Ok((rest, PseudoClass::Empty)),
+        "first-child" => Ok((rest, PseudoClass::FirstChild)),
+        "last-child" => Ok((rest, PseudoClass::LastChild)),
+        "nth-child" => map(nth_argument, PseudoClass::NthChild)(rest),
+        "nth-of-type" => map(nth_argument, PseudoClass::NthOfType)(rest),
+        "not" => {
+            let (argument, _) = tuple((cut(expect("`(`", tag("("))), many0(whitespace)))(rest)?;
+            let (rest, negated) = cut(accessor)(argument)?;
+            if let Some(step) = negated.step() {
+                return Err(unsupported(argument, format!("{} in `:not()`", step)));
+            }
+            // `:not()` tells apart a single node, not where it is in the document
+            if preceded(combinator, accessor)(rest).is_ok() {
+                let feature = "a combinator in `:not()`".to_owned();
+                return Err(unsupported(rest, feature));
+            }
+            let (rest, _) = cut(tuple((many0(whitespace), expect("`)`", tag(")")))))(rest)?;
+            Ok((rest, PseudoClass::Not(negated)))
+        }
+        _ => Err(unsupported(input, format!("pseudo-class `:{}`", name))),
+    }
+}
+
+fn nth_argument(input: &str) -> IResult<'_, Nth> {
+    delimited(
+        tuple((cut(expect("`(`", tag("("))), many0(whitespace))),
+        cut(nth),
+        cut(tuple((many0(whitespace), expect("`)`", tag(")"))))),
+    )(input)
+}
+
+/// ```text
+/// nth :=
+///   'odd' |
+///   'even' |
+///   sign? digit* 'n' (ws* sign ws* digit+)? |
+///   sign? digit+
+/// ```
+fn nth(input: &str) -> IResult<'_, Nth> {
+    let integer = |sign: Option<char>, digits: &str| {
+        let magnitude = digits.parse::<i64>().unwrap_or(i64::MAX);
+        match sign {
+            Some('-') => -magnitude,
+            _ => magnitude,
+        }
+    };
+    expect(
+        "`an+b`",
+        alt((
+            value(Nth { step: 2, offset: 1 }, tag("odd")),
+            value(Nth { step: 2, offset: 0 }, tag("even")),
+            map(
+                tuple((
+                    opt(one_of("+-")),
+                    digit0,
+                    one_of("nN"),
+                    opt(tuple((
+                        preceded(many0(whitespace), one_of("+-")),
+                        preceded(many0(whitespace), digit1),
+                    ))),
+                )),
+                move |(sign, step, _, offset)| Nth {
+                    step: integer(sign, if step.is_empty() { "1" } else { step }),
+                    offset: offset.map_or(0, |(sign, offset)| integer(Some(sign), offset)),
+                },
+            ),
+            map(tuple((opt(one_of("+-")), digit1)), move |(sign, offset)| {
+                Nth {
+                    step: 0,
+                    offset: integer(sign, offset),
+                }
+            }),
+        )),
+    )(input)
+}
+
+/// ```text
+/// matcher :=
+///   '[' count ']' |
+///   '[' entity ']' |
+///   '[' entity ws+ '~=' ws+ type-annotation? string case ']' |
+///   '[' entity ws+ operator ws+ type-annotation? kdl-value case ']'
+/// ```
+fn matcher(input: &str) -> IResult<'_, Matcher> {
     let (input, _) = tag("[")(input)?;
-    let (input, left_hand_side) = entity(input)?;
-    let (input, expression) = opt(tuple((
-        delimited(many1(kdlrs::whitespace), operator, many1(kdlrs::whitespace)),
-        kdlrs::node_value,
+    match count(input) {
+        Ok((input, count)) => {
+            let (input, _) = cut(expect("`]`", tag("]")))(input)?;
+            return Ok((input, count));
+        }
+        Err(nom::Err::Error(_)) => {}
+        Err(error) => return Err(error),
+    }
+    let (input, left_hand_side) = cut(entity)(input)?;
+    let (input, expression) = cut(alt((
+        map(expect("`]`", tag("]")), |_| None),
+        map(
+            terminated(
+                tuple((
+                    preceded(
+                        expect("an operator", many1(whitespace)),
+                        alt((
+                            pattern,
+                            tuple((cut(operator), cut(preceded(many1(whitespace), node_value)))),
+                        )),
+                    ),
+                    case,
+                )),
+                cut(expect("`]`", tag("]"))),
+            ),
+            Some,
+        ),
     )))(input)?;
-    let (input, _) = tag("]")(input)?;
 
     let output = match expression {
-        Some((operator, right_hand_side)) => {
-            Matcher::Expression(left_hand_side, operator, right_hand_side)
+        Some(((operator, (type_tag, right_hand_side)), case)) => {
+            let mut matcher = Matcher::Expression(
+                left_hand_side,
+                operator,
+                type_tag,
+                right_hand_side,
+                Case::Sensitive,
+            );
+            if case == Case::Insensitive {
+                matcher.ignore_case();
+            }
+            matcher
         }
         None => Matcher::Direct(left_hand_side),
     };
@@ -189,6 +841,40 @@ fn matcher(input: &str) -> IResult<&str, Matcher> {
     Ok((input, output))
 }
 
+/// `count := 'count(' ('values()' | 'props()') ')' ws+ operator ws+ type-annotation? kdl-value`
+fn count(input: &str) -> IResult<'_, Matcher> {
+    let (input, entity) = preceded(
+        tag("count("),
+        cut(terminated(
+            alt((
+                value(Entity::Values, expect("`values()`", tag("values()"))),
+                value(Entity::Props, expect("`props()`", tag("props()"))),
+            )),
+            expect("`)`", tag(")")),
+        )),
+    )(input)?;
+    let (input, (operator, (type_tag, right_hand_side))) = cut(tuple((
+        preceded(expect("an operator", many1(whitespace)), operator),
+        preceded(many1(whitespace), node_value),
+    )))(input)?;
+
+    Ok((
+        input,
+        Matcher::Count(entity, operator, type_tag, right_hand_side),
+    ))
+}
+
+/// `case := (ws+ ('i' | 'I'))?`
+fn case(input: &str) -> IResult<'_, Case> {
+    map(
+        opt(preceded(many1(whitespace), alt((tag("i"), tag("I"))))),
+        |modifier| match modifier {
+            Some(_) => Case::Insensitive,
+            None => Case::Sensitive,
+        },
+    )(input)
+}
+
 /// ```text
 /// entity :=
 ///   'name()' |
@@ -199,44 +885,374 @@ fn matcher(input: &str) -> IResult<&str, Matcher> {
 ///   'prop(' identifier ')' |
 ///   identifier '()'?
 /// ```
-fn entity(input: &str) -> IResult<&str, Entity> {
+fn entity(input: &str) -> IResult<'_, Entity> {
+    expect(
+        "an entity",
+        alt((
+            value(Entity::NodeName, tag("name()")),
+            value(Entity::TypeTag, tag("tag()")),
+            value(Entity::Props, tag("props()")),
+            value(Entity::Values, tag("values()")),
+            map(
+                delimited(tag("val("), digit0, cut(expect("`)`", tag(")")))),
+                |input: &str| Entity::Val(input.parse::<usize>().unwrap_or(0)),
+            ),
+            map(
+                delimited(tag("prop("), cut(identifier), cut(expect("`)`", tag(")")))),
+                Entity::PropName,
+            ),
+            map(identifier, Entity::PropName),
+        )),
+    )(input)
+}
+
+/// `mapping := entity | '(' ws* entity (ws* ',' ws* entity)* ws* ')'`
+fn mapping(input: &str) -> IResult<'_, Mapping> {
     alt((
-        value(Entity::NodeName, tag("name()")),
-        value(Entity::TypeTag, tag("tag()")),
-        value(Entity::Props, tag("props()")),
-        value(Entity::Values, tag("values()")),
-        map(delimited(tag("val("), digit0, tag(")")), |input: &str| {
-            Entity::Val(input.parse::<usize>().unwrap_or(0))
-        }),
         map(
-            delimited(tag("prop("), kdlrs::identifier, tag(")")),
-            Entity::PropName,
+            delimited(
+                terminated(tag("("), many0(whitespace)),
+                cut(tuple((
+                    entity,
+                    many0(preceded(
+                        delimited(many0(whitespace), tag(","), many0(whitespace)),
+                        cut(entity),
+                    )),
+                ))),
+                cut(preceded(many0(whitespace), expect("`)`", tag(")")))),
+            ),
+            |(head, tail)| Mapping::Tuple(iter::once(head).chain(tail).collect()),
         ),
-        map(kdlrs::identifier, Entity::PropName),
+        map(entity, Mapping::Entity),
     ))(input)
 }
 
+/// `update := assignment (ws* ',' ws* assignment)*`
+fn update(input: &str) -> IResult<'_, Update> {
+    let (input, head) = assignment(input)?;
+    let (input, tail) = many0(preceded(
+        delimited(many0(whitespace), tag(","), many0(whitespace)),
+        cut(assignment),
+    ))(input)?;
+
+    Ok((input, iter::once(head).chain(tail).collect()))
+}
+
+/// ```text
+/// assignment :=
+///   'del(' entity ')' |
+///   entity ws+ '=' ws+ type-annotation? kdl-value |
+///   entity ws+ '+=' ws+ type-annotation? kdl-value
+/// ```
+fn assignment(input: &str) -> IResult<'_, (Entity, Edit)> {
+    let (rest, (entity, edit)) = expect(
+        "an assignment",
+        alt((
+            map(
+                delimited(tag("del("), cut(entity), cut(expect("`)`", tag(")")))),
+                |entity| (entity, Edit::Delete),
+            ),
+            map(
+                tuple((
+                    entity,
+                    preceded(
+                        expect("`=` or `+=`", many1(whitespace)),
+                        cut(expect("`=` or `+=`", alt((tag("+="), tag("="))))),
+                    ),
+                    cut(preceded(many1(whitespace), consumed(node_value))),
+                )),
+                |(entity, operator, (text, (ty, value)))| {
+                    let entry = entry(text, ty, value);
+                    match operator {
+                        "+=" => (entity, Edit::Append(entry)),
+                        _ => (entity, Edit::Set(entry)),
+                    }
+                },
+            ),
+        )),
+    )(input)?;
+
+    match edit::check(&edit, Some(&entity)) {
+        Some(feature) => Err(unsupported(input, feature)),
+        None => Ok((rest, (entity, edit))),
+    }
+}
+
+/// The entry of a node argument written as `text`, so that an update keeps how a value is
+/// written, e.g. `0xFF`.
+fn entry(text: &str, ty: Option<String>, value: KdlValue) -> KdlEntry {
+    let parsed = format!("- {}", text)
+        .parse::<KdlDocument>()
+        .ok()
+        .and_then(|document| Some(document.nodes().first()?.entries().first()?.clone()));
+
+    parsed.unwrap_or_else(|| {
+        let mut entry = KdlEntry::new(value);
+        if let Some(ty) = ty {
+            entry.set_ty(ty.as_str());
+        }
+        entry
+    })
+}
+
+/// `'~=' ws+ type-annotation? string`, where the string is a regular expression, which matches
+/// anywhere in a value unless it is anchored with `^` or `$`.
+fn pattern(input: &str) -> IResult<'_, (Operator, (Option<String>, KdlValue))> {
+    let (input, _) = tag("~=")(input)?;
+    let (input, _) = cut(many1(whitespace))(input)?;
+    let (rest, (type_tag, value)) = cut(node_value)(input)?;
+
+    let source = match &value {
+        KdlValue::RawString(string) | KdlValue::String(string) => string,
+        _ => return Err(nom::Err::Failure(Error::expected(input, "a string"))),
+    };
+    match Regex::new(source) {
+        Ok(regex) => Ok((rest, (Operator::Matches(Pattern(regex)), (type_tag, value)))),
+        Err(error) => Err(nom::Err::Failure(Error {
+            input,
+            kind: ErrorKind::Pattern(describe_pattern_error(&error)),
+        })),
+    }
+}
+
+/// The reason a pattern is invalid, without the copy of the pattern that regex errors start with.
+fn describe_pattern_error(error: &regex::Error) -> String {
+    let message = error.to_string();
+    let reason = message.lines().last().unwrap_or_default();
+    reason.trim_start_matches("error: ").to_owned()
+}
+
 /// `operator := '=' | '!=' | '>' | '>=' | '<' | '<=' | '^=' | '$=' | '*='`
-fn operator(input: &str) -> IResult<&str, Operator> {
-    alt((
-        value(Operator::Contains, tag("*=")),
-        value(Operator::EndsWith, tag("$=")),
-        value(Operator::GreaterThanOrEqualTo, tag(">=")),
-        value(Operator::LessThanOrEqualTo, tag("<=")),
-        value(Operator::NotEqual, tag("!=")),
-        value(Operator::StartsWith, tag("^=")),
-        value(Operator::Equal, tag("=")),
-        value(Operator::GreaterThan, tag(">")),
-        value(Operator::LessThan, tag("<")),
-    ))(input)
+fn operator(input: &str) -> IResult<'_, Operator> {
+    expect(
+        "an operator",
+        alt((
+            value(Operator::Contains, tag("*=")),
+            value(Operator::EndsWith, tag("$=")),
+            value(Operator::GreaterThanOrEqualTo, tag(">=")),
+            value(Operator::LessThanOrEqualTo, tag("<=")),
+            value(Operator::NotEqual, tag("!=")),
+            value(Operator::StartsWith, tag("^=")),
+            value(Operator::Equal, tag("=")),
+            value(Operator::GreaterThan, tag(">")),
+            value(Operator::LessThan, tag("<")),
+        )),
+    )(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_alternation() {
+        assert_eq!(
+            alternation("a"),
+            Ok((
+                "",
+                vec![vec![Combinator::Descendant(
+                    Accessor::Sole("a".to_owned(), Case::Sensitive),
+                    vec![]
+                )]]
+            ))
+        );
+
+        assert_eq!(
+            alternation("a > b || c"),
+            Ok((
+                "",
+                vec![
+                    vec![
+                        Combinator::Descendant(
+                            Accessor::Sole("a".to_owned(), Case::Sensitive),
+                            vec![]
+                        ),
+                        Combinator::Child(Accessor::Sole("b".to_owned(), Case::Sensitive), vec![])
+                    ],
+                    vec![Combinator::Descendant(
+                        Accessor::Sole("c".to_owned(), Case::Sensitive),
+                        vec![]
+                    )]
+                ]
+            ))
+        );
+
+        assert_eq!(
+            alternation("[]||b"),
+            Ok((
+                "",
+                vec![
+                    vec![Combinator::Descendant(Accessor::AnyElement, vec![])],
+                    vec![Combinator::Descendant(
+                        Accessor::Sole("b".to_owned(), Case::Sensitive),
+                        vec![]
+                    )]
+                ]
+            ))
+        );
+
+        assert_eq!(
+            alternation("a ||"),
+            Err(nom::Err::Failure(Error::expected("", "an accessor")))
+        );
+    }
+
+    #[test]
+    fn test_error() {
+        assert_eq!(
+            query("a[b"),
+            Err(nom::Err::Failure(Error {
+                input: "",
+                kind: ErrorKind::Expected(vec!["`]`", "an operator"])
+            }))
+        );
+        assert_eq!(
+            query("a > b[c = d]"),
+            Err(nom::Err::Failure(Error::expected("d]", "a value")))
+        );
+        assert_eq!(
+            query("a => (name(), )"),
+            Err(nom::Err::Failure(Error::expected(")", "an entity")))
+        );
+        assert_eq!(
+            query("a >"),
+            Err(nom::Err::Failure(Error::expected(
+                "",
+                "a selector after `>`"
+            )))
+        );
+        assert_eq!(
+            query("[]:has(a >)"),
+            Err(nom::Err::Failure(Error::expected(
+                ")",
+                "a selector after `>`"
+            )))
+        );
+        assert_eq!(
+            query("a ~ "),
+            Err(nom::Err::Failure(Error::expected(
+                "",
+                "a selector after `~`"
+            )))
+        );
+        assert_eq!(
+            query("a >b"),
+            Err(nom::Err::Failure(Error::expected("b", "whitespace")))
+        );
+        assert_eq!(
+            query("[count(val()) > 1]"),
+            Err(nom::Err::Failure(Error {
+                input: "val()) > 1]",
+                kind: ErrorKind::Expected(vec!["`values()`", "`props()`"])
+            }))
+        );
+        assert_eq!(
+            query("[]:hover"),
+            Err(nom::Err::Failure(Error {
+                input: ":hover",
+                kind: ErrorKind::Unsupported("pseudo-class `:hover`".to_owned())
+            }))
+        );
+        assert_eq!(
+            query("[]:not(a > b)"),
+            Err(nom::Err::Failure(Error {
+                input: " > b)",
+                kind: ErrorKind::Unsupported("a combinator in `:not()`".to_owned())
+            }))
+        );
+        assert_eq!(
+            query("[]:has(~ a)"),
+            Err(nom::Err::Failure(Error {
+                input: "~ a)",
+                kind: ErrorKind::Unsupported("a sibling combinator in `:has()`".to_owned())
+            }))
+        );
+        assert_eq!(
+            query("top():not(a)"),
+            Err(nom::Err::Failure(Error {
+                input: ":not(a)",
+                kind: ErrorKind::Unsupported("a pseudo-class on `top()`".to_owned())
+            }))
+        );
+        assert_eq!(
+            query("a > top()"),
+            Err(nom::Err::Failure(Error {
+                input: "top()",
+                kind: ErrorKind::Unsupported("`top()` in the middle of a selector".to_owned())
+            }))
+        );
+        assert_eq!(
+            query("top() ~ a"),
+            Err(nom::Err::Failure(Error {
+                input: "~ a",
+                kind: ErrorKind::Unsupported("`~` after `top()`".to_owned())
+            }))
+        );
+        assert_eq!(
+            query("top() ancestor()"),
+            Err(nom::Err::Failure(Error {
+                input: "ancestor()",
+                kind: ErrorKind::Unsupported("`ancestor()` after `top()`".to_owned())
+            }))
+        );
+        assert_eq!(
+            query("[]:has(top() > a)"),
+            Err(nom::Err::Failure(Error {
+                input: "top() > a)",
+                kind: ErrorKind::Unsupported("`top()` in `:has()`".to_owned())
+            }))
+        );
+        assert_eq!(
+            query("parent() > a"),
+            Err(nom::Err::Failure(Error {
+                input: "parent() > a",
+                kind: ErrorKind::Unsupported("`parent()` at the start of a selector".to_owned())
+            }))
+        );
+        assert_eq!(
+            query("a > ancestor(b)"),
+            Err(nom::Err::Failure(Error {
+                input: "ancestor(b)",
+                kind: ErrorKind::Unsupported("`ancestor()` after `>`".to_owned())
+            }))
+        );
+        assert_eq!(
+            query("a parent() ~ b"),
+            Err(nom::Err::Failure(Error {
+                input: "~ b",
+                kind: ErrorKind::Unsupported("`~` after `parent()`".to_owned())
+            }))
+        );
+        assert_eq!(
+            query("a ancestor(parent())"),
+            Err(nom::Err::Failure(Error {
+                input: "parent())",
+                kind: ErrorKind::Unsupported("`parent()` in `ancestor()`".to_owned())
+            }))
+        );
+        assert_eq!(
+            end(", a"),
+            Err(nom::Err::Error(Error::expected(
+                ", a",
+                "the end of the selector"
+            )))
+        );
+    }
+
     #[test]
     fn test_selector() {
+        assert_eq!(
+            selector("a parent() > b"),
+            Ok((
+                "",
+                vec![
+                    Combinator::Descendant(Accessor::Sole("a".to_owned(), Case::Sensitive), vec![]),
+                    Combinator::Descendant(Accessor::Parent, vec![]),
+                    Combinator::Child(Accessor::Sole("b".to_owned(), Case::Sensitive), vec![])
+                ]
+            ))
+        );
+
         assert_eq!(
             selector("top()"),
             Ok(("", vec![Combinator::Descendant(Accessor::Top, vec![]),]))
@@ -269,10 +1285,16 @@ mod tests {
             Ok((
                 "",
                 vec![Combinator::Descendant(
-                    Accessor::Sole("a".to_owned()),
+                    Accessor::Sole("a".to_owned(), Case::Sensitive),
                     vec![
-                        (Sibling::Adjacent, Accessor::Sole("b".to_owned())),
-                        (Sibling::General, Accessor::Sole("c".to_owned()))
+                        (
+                            Sibling::Adjacent,
+                            Accessor::Sole("b".to_owned(), Case::Sensitive)
+                        ),
+                        (
+                            Sibling::General,
+                            Accessor::Sole("c".to_owned(), Case::Sensitive)
+                        )
                     ]
                 )]
             ))
@@ -284,10 +1306,16 @@ mod tests {
                 "",
                 vec![
                     Combinator::Descendant(
-                        Accessor::Sole("a".to_owned()),
+                        Accessor::Sole("a".to_owned(), Case::Sensitive),
                         vec![
-                            (Sibling::Adjacent, Accessor::Sole("b".to_owned())),
-                            (Sibling::General, Accessor::Sole("c".to_owned()))
+                            (
+                                Sibling::Adjacent,
+                                Accessor::Sole("b".to_owned(), Case::Sensitive)
+                            ),
+                            (
+                                Sibling::General,
+                                Accessor::Sole("c".to_owned(), Case::Sensitive)
+                            )
                         ]
                     ),
                     Combinator::Child(Accessor::AnyElement, vec![])
@@ -301,10 +1329,16 @@ mod tests {
                 "",
                 vec![
                     Combinator::Descendant(
-                        Accessor::Sole("a".to_owned()),
+                        Accessor::Sole("a".to_owned(), Case::Sensitive),
                         vec![
-                            (Sibling::Adjacent, Accessor::Sole("b".to_owned())),
-                            (Sibling::General, Accessor::Sole("c".to_owned()))
+                            (
+                                Sibling::Adjacent,
+                                Accessor::Sole("b".to_owned(), Case::Sensitive)
+                            ),
+                            (
+                                Sibling::General,
+                                Accessor::Sole("c".to_owned(), Case::Sensitive)
+                            )
                         ]
                     ),
                     Combinator::Descendant(Accessor::AnyElement, vec![])
@@ -318,17 +1352,29 @@ mod tests {
                 "",
                 vec![
                     Combinator::Descendant(
-                        Accessor::Sole("a".to_owned()),
+                        Accessor::Sole("a".to_owned(), Case::Sensitive),
                         vec![
-                            (Sibling::Adjacent, Accessor::Sole("b".to_owned())),
-                            (Sibling::General, Accessor::Sole("c".to_owned()))
+                            (
+                                Sibling::Adjacent,
+                                Accessor::Sole("b".to_owned(), Case::Sensitive)
+                            ),
+                            (
+                                Sibling::General,
+                                Accessor::Sole("c".to_owned(), Case::Sensitive)
+                            )
                         ]
                     ),
                     Combinator::Descendant(
-                        Accessor::Sole("d".to_owned()),
+                        Accessor::Sole("d".to_owned(), Case::Sensitive),
                         vec![
-                            (Sibling::General, Accessor::Sole("e".to_owned())),
-                            (Sibling::Adjacent, Accessor::Sole("f".to_owned()))
+                            (
+                                Sibling::General,
+                                Accessor::Sole("e".to_owned(), Case::Sensitive)
+                            ),
+                            (
+                                Sibling::Adjacent,
+                                Accessor::Sole("f".to_owned(), Case::Sensitive)
+                            )
                         ]
                     ),
                 ]
@@ -341,17 +1387,29 @@ mod tests {
                 "",
                 vec![
                     Combinator::Descendant(
-                        Accessor::Sole("a".to_owned()),
+                        Accessor::Sole("a".to_owned(), Case::Sensitive),
                         vec![
-                            (Sibling::Adjacent, Accessor::Sole("b".to_owned())),
-                            (Sibling::General, Accessor::Sole("c".to_owned()))
+                            (
+                                Sibling::Adjacent,
+                                Accessor::Sole("b".to_owned(), Case::Sensitive)
+                            ),
+                            (
+                                Sibling::General,
+                                Accessor::Sole("c".to_owned(), Case::Sensitive)
+                            )
                         ]
                     ),
                     Combinator::Child(
-                        Accessor::Sole("d".to_owned()),
+                        Accessor::Sole("d".to_owned(), Case::Sensitive),
                         vec![
-                            (Sibling::General, Accessor::Sole("e".to_owned())),
-                            (Sibling::Adjacent, Accessor::Sole("f".to_owned()))
+                            (
+                                Sibling::General,
+                                Accessor::Sole("e".to_owned(), Case::Sensitive)
+                            ),
+                            (
+                                Sibling::Adjacent,
+                                Accessor::Sole("f".to_owned(), Case::Sensitive)
+                            )
                         ]
                     ),
                 ]
@@ -365,10 +1423,16 @@ mod tests {
                 vec![
                     Combinator::Descendant(Accessor::Top, vec![]),
                     Combinator::Descendant(
-                        Accessor::Sole("a".to_owned()),
+                        Accessor::Sole("a".to_owned(), Case::Sensitive),
                         vec![
-                            (Sibling::Adjacent, Accessor::Sole("b".to_owned())),
-                            (Sibling::General, Accessor::Sole("c".to_owned()))
+                            (
+                                Sibling::Adjacent,
+                                Accessor::Sole("b".to_owned(), Case::Sensitive)
+                            ),
+                            (
+                                Sibling::General,
+                                Accessor::Sole("c".to_owned(), Case::Sensitive)
+                            )
                         ]
                     ),
                     Combinator::Child(Accessor::AnyElement, vec![])
@@ -383,10 +1447,16 @@ mod tests {
                 vec![
                     Combinator::Descendant(Accessor::Top, vec![]),
                     Combinator::Child(
-                        Accessor::Sole("a".to_owned()),
+                        Accessor::Sole("a".to_owned(), Case::Sensitive),
                         vec![
-                            (Sibling::Adjacent, Accessor::Sole("b".to_owned())),
-                            (Sibling::General, Accessor::Sole("c".to_owned()))
+                            (
+                                Sibling::Adjacent,
+                                Accessor::Sole("b".to_owned(), Case::Sensitive)
+                            ),
+                            (
+                                Sibling::General,
+                                Accessor::Sole("c".to_owned(), Case::Sensitive)
+                            )
                         ]
                     ),
                     Combinator::Descendant(Accessor::AnyElement, vec![])
@@ -395,6 +1465,144 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_query() {
+        assert_eq!(
+            query("a"),
+            Ok((
+                "",
+                (
+                    vec![vec![Combinator::Descendant(
+                        Accessor::Sole("a".to_owned(), Case::Sensitive),
+                        vec![]
+                    )]],
+                    None
+                )
+            ))
+        );
+
+        assert_eq!(
+            query("a > b => val()"),
+            Ok((
+                "",
+                (
+                    vec![vec![
+                        Combinator::Descendant(
+                            Accessor::Sole("a".to_owned(), Case::Sensitive),
+                            vec![]
+                        ),
+                        Combinator::Child(Accessor::Sole("b".to_owned(), Case::Sensitive), vec![])
+                    ]],
+                    Some(Clause::Mapping("val()", Mapping::Entity(Entity::Val(0))))
+                )
+            ))
+        );
+
+        assert_eq!(
+            query("a => (name(), prop(b))"),
+            Ok((
+                "",
+                (
+                    vec![vec![Combinator::Descendant(
+                        Accessor::Sole("a".to_owned(), Case::Sensitive),
+                        vec![]
+                    )]],
+                    Some(Clause::Mapping(
+                        "(name(), prop(b))",
+                        Mapping::Tuple(vec![Entity::NodeName, Entity::PropName("b".to_owned())])
+                    ))
+                )
+            ))
+        );
+
+        assert_eq!(
+            query("a =>"),
+            Err(nom::Err::Failure(Error::expected("", "whitespace")))
+        );
+
+        assert_eq!(
+            query("a |= del(b)"),
+            Ok((
+                "",
+                (
+                    vec![vec![Combinator::Descendant(
+                        Accessor::Sole("a".to_owned(), Case::Sensitive),
+                        vec![]
+                    )]],
+                    Some(Clause::Update(vec![(
+                        Entity::PropName("b".to_owned()),
+                        Edit::Delete
+                    )]))
+                )
+            ))
+        );
+
+        assert_eq!(
+            query("a |="),
+            Err(nom::Err::Failure(Error::expected("", "whitespace")))
+        );
+    }
+
+    fn argument(value: &str) -> KdlEntry {
+        let document = format!("- {}", value).parse::<KdlDocument>().unwrap();
+        document.nodes()[0].entries()[0].clone()
+    }
+
+    #[test]
+    fn test_update() {
+        assert_eq!(
+            update("val() = 0xFF, values() += (semver)\"1.0\", del(tag())"),
+            Ok((
+                "",
+                vec![
+                    (Entity::Val(0), Edit::Set(argument("0xFF"))),
+                    (Entity::Values, Edit::Append(argument("(semver)\"1.0\""))),
+                    (Entity::TypeTag, Edit::Delete)
+                ]
+            ))
+        );
+
+        assert_eq!(
+            update("dev ="),
+            Err(nom::Err::Failure(Error::expected("", "whitespace")))
+        );
+        assert_eq!(
+            update("dev : true"),
+            Err(nom::Err::Failure(Error::expected(": true", "`=` or `+=`")))
+        );
+        assert_eq!(
+            update("val() += 1"),
+            Err(nom::Err::Failure(Error {
+                input: "val() += 1",
+                kind: ErrorKind::Unsupported("appending to `val(0)`".to_owned())
+            }))
+        );
+    }
+
+    #[test]
+    fn test_mapping() {
+        assert_eq!(
+            mapping("name()"),
+            Ok(("", Mapping::Entity(Entity::NodeName)))
+        );
+        assert_eq!(mapping("val(1)"), Ok(("", Mapping::Entity(Entity::Val(1)))));
+        assert_eq!(
+            mapping("version"),
+            Ok(("", Mapping::Entity(Entity::PropName("version".to_owned()))))
+        );
+        assert_eq!(
+            mapping("(name(),val())"),
+            Ok(("", Mapping::Tuple(vec![Entity::NodeName, Entity::Val(0)])))
+        );
+        assert_eq!(
+            mapping("( props() , values() )"),
+            Ok(("", Mapping::Tuple(vec![Entity::Props, Entity::Values])))
+        );
+
+        assert!(mapping("()").is_err());
+        assert!(mapping("(name(),)").is_err());
+    }
+
     #[test]
     fn test_combinator() {
         use super::ParsedCombinator::{AdjacentSibling, Child, Descendant, GeneralSibling};
@@ -407,22 +1615,244 @@ mod tests {
 
     #[test]
     fn test_accessor() {
-        use super::Accessor::{AnyElement, Closed, Sole, Top};
+        use super::Accessor::{AnyElement, AnyElementWithTypeTag, Closed, Pseudo, Sole, Top};
 
         assert_eq!(accessor("[]"), Ok(("", AnyElement)));
-        assert_eq!(accessor("name"), Ok(("", Sole("name".to_owned()))));
+        assert_eq!(
+            accessor("name"),
+            Ok(("", Sole("name".to_owned(), Case::Sensitive)))
+        );
         assert_eq!(accessor("top()"), Ok(("", Top)));
+        assert_eq!(
+            accessor("()"),
+            Ok(("", AnyElementWithTypeTag(None, Case::Sensitive)))
+        );
+        assert_eq!(
+            accessor("(author)"),
+            Ok((
+                "",
+                AnyElementWithTypeTag(Some("author".to_owned()), Case::Sensitive)
+            ))
+        );
         assert_eq!(
             accessor("[props()]"),
-            Ok(("", Closed(None, Matcher::Direct(Entity::Props))))
+            Ok((
+                "",
+                Closed(None, vec![Matcher::Direct(Entity::Props)], Case::Sensitive)
+            ))
         );
         assert_eq!(
             accessor("name[props()]"),
             Ok((
                 "",
-                Closed(Some("name".to_owned()), Matcher::Direct(Entity::Props))
+                Closed(
+                    Some("name".to_owned()),
+                    vec![Matcher::Direct(Entity::Props)],
+                    Case::Sensitive
+                )
+            ))
+        );
+        assert_eq!(
+            accessor(r#"package[version][name = "foo"]"#),
+            Ok((
+                "",
+                Closed(
+                    Some("package".to_owned()),
+                    vec![
+                        Matcher::Direct(Entity::PropName("version".to_owned())),
+                        Matcher::Expression(
+                            Entity::PropName("name".to_owned()),
+                            Operator::Equal,
+                            None,
+                            "foo".into(),
+                            Case::Sensitive
+                        )
+                    ],
+                    Case::Sensitive
+                )
             ))
         );
+        assert_eq!(
+            accessor("[val()][prop]"),
+            Ok((
+                "",
+                Closed(
+                    None,
+                    vec![
+                        Matcher::Direct(Entity::Val(0)),
+                        Matcher::Direct(Entity::PropName("prop".to_owned()))
+                    ],
+                    Case::Sensitive
+                )
+            ))
+        );
+        assert_eq!(
+            accessor("name[val()] [prop]"),
+            Ok((
+                " [prop]",
+                Closed(
+                    Some("name".to_owned()),
+                    vec![Matcher::Direct(Entity::Val(0))],
+                    Case::Sensitive
+                )
+            ))
+        );
+        assert_eq!(
+            accessor("dependencies:not([platform]):not( (dev) )"),
+            Ok((
+                "",
+                Pseudo(
+                    Box::new(Sole("dependencies".to_owned(), Case::Sensitive)),
+                    vec![
+                        PseudoClass::Not(Closed(
+                            None,
+                            vec![Matcher::Direct(Entity::PropName("platform".to_owned()))],
+                            Case::Sensitive
+                        )),
+                        PseudoClass::Not(AnyElementWithTypeTag(
+                            Some("dev".to_owned()),
+                            Case::Sensitive
+                        ))
+                    ]
+                )
+            ))
+        );
+        assert_eq!(
+            accessor("[]:not(a:not(b))"),
+            Ok((
+                "",
+                Pseudo(
+                    Box::new(AnyElement),
+                    vec![PseudoClass::Not(Pseudo(
+                        Box::new(Sole("a".to_owned(), Case::Sensitive)),
+                        vec![PseudoClass::Not(Sole("b".to_owned(), Case::Sensitive))]
+                    ))]
+                )
+            ))
+        );
+        assert_eq!(
+            accessor("dependencies:nth-of-type(2):empty"),
+            Ok((
+                "",
+                Pseudo(
+                    Box::new(Sole("dependencies".to_owned(), Case::Sensitive)),
+                    vec![
+                        PseudoClass::NthOfType(Nth { step: 0, offset: 2 }),
+                        PseudoClass::Empty
+                    ]
+                )
+            ))
+        );
+        assert_eq!(
+            accessor("[]:first-child:last-child"),
+            Ok((
+                "",
+                Pseudo(
+                    Box::new(AnyElement),
+                    vec![PseudoClass::FirstChild, PseudoClass::LastChild]
+                )
+            ))
+        );
+        assert_eq!(
+            accessor("a:empty-ish"),
+            Ok(("", Sole("a:empty-ish".to_owned(), Case::Sensitive)))
+        );
+        assert_eq!(
+            accessor("package:has(> dependencies [dev])"),
+            Ok((
+                "",
+                Pseudo(
+                    Box::new(Sole("package".to_owned(), Case::Sensitive)),
+                    vec![PseudoClass::Has(vec![
+                        Combinator::Child(Sole("dependencies".to_owned(), Case::Sensitive), vec![]),
+                        Combinator::Descendant(
+                            Closed(
+                                None,
+                                vec![Matcher::Direct(Entity::PropName("dev".to_owned()))],
+                                Case::Sensitive
+                            ),
+                            vec![]
+                        )
+                    ])]
+                )
+            ))
+        );
+        assert_eq!(
+            accessor(":not(:has(a))"),
+            Ok((
+                "",
+                Pseudo(
+                    Box::new(AnyElement),
+                    vec![PseudoClass::Not(Pseudo(
+                        Box::new(AnyElement),
+                        vec![PseudoClass::Has(vec![Combinator::Descendant(
+                            Sole("a".to_owned(), Case::Sensitive),
+                            vec![]
+                        )])]
+                    ))]
+                )
+            ))
+        );
+        assert_eq!(
+            accessor("xml:lang"),
+            Ok(("", Sole("xml:lang".to_owned(), Case::Sensitive)))
+        );
+        assert_eq!(accessor("parent()"), Ok(("", Accessor::Parent)));
+        assert_eq!(accessor("ancestor()"), Ok(("", Accessor::Ancestor(None))));
+        assert_eq!(
+            accessor("ancestor( package )"),
+            Ok((
+                "",
+                Accessor::Ancestor(Some(Box::new(Sole("package".to_owned(), Case::Sensitive))))
+            ))
+        );
+        assert_eq!(
+            accessor(r#""a:not(b)""#),
+            Ok(("", Sole("a:not(b)".to_owned(), Case::Sensitive)))
+        );
+    }
+
+    #[test]
+    fn test_nth() {
+        let nth = |step, offset| Ok(("", Nth { step, offset }));
+
+        assert_eq!(nth_argument("(odd)"), nth(2, 1));
+        assert_eq!(nth_argument("( even )"), nth(2, 0));
+        assert_eq!(nth_argument("(3)"), nth(0, 3));
+        assert_eq!(nth_argument("(+3)"), nth(0, 3));
+        assert_eq!(nth_argument("(n)"), nth(1, 0));
+        assert_eq!(nth_argument("(2n+1)"), nth(2, 1));
+        assert_eq!(nth_argument("(-n + 3)"), nth(-1, 3));
+        assert_eq!(nth_argument("(3n - 2)"), nth(3, -2));
+        assert_eq!(
+            nth_argument("(n+)"),
+            Err(nom::Err::Failure(Error::expected("+)", "`)`")))
+        );
+
+        let positions = |nth: Nth| (1..=7).filter(|i| nth.matches(*i)).collect::<Vec<_>>();
+        assert_eq!(positions(Nth { step: 2, offset: 1 }), [1, 3, 5, 7]);
+        assert_eq!(positions(Nth { step: 0, offset: 3 }), [3]);
+        assert_eq!(
+            positions(Nth {
+                step: -1,
+                offset: 3
+            }),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            positions(Nth {
+                step: 3,
+                offset: -2
+            }),
+            [1, 4, 7]
+        );
+        assert_eq!(
+            positions(Nth {
+                step: 0,
+                offset: -1
+            }),
+            []
+        );
     }
 
     #[test]
@@ -445,40 +1875,103 @@ mod tests {
         );
         assert!(matcher("[some()]").is_err());
 
+        assert_eq!(
+            matcher(r#"[prop(uses) ~= r"@v\d+$"]"#),
+            Ok((
+                "",
+                Expression(
+                    Entity::PropName("uses".to_owned()),
+                    Operator::Matches(Pattern(Regex::new(r"@v\d+$").unwrap())),
+                    None,
+                    KdlValue::RawString(r"@v\d+$".to_owned()),
+                    Case::Sensitive
+                )
+            ))
+        );
+        assert_eq!(
+            matcher(r#"[val() ~= "("]"#),
+            Err(nom::Err::Failure(Error {
+                input: r#""("]"#,
+                kind: ErrorKind::Pattern("unclosed group".to_owned())
+            }))
+        );
+
         assert_eq!(
             matcher(r#"[name() = "kdl"]"#),
             Ok((
                 "",
-                Expression(Entity::NodeName, Operator::Equal, "kdl".into())
+                Expression(
+                    Entity::NodeName,
+                    Operator::Equal,
+                    None,
+                    "kdl".into(),
+                    Case::Sensitive
+                )
             ))
         );
         assert_eq!(
             matcher(r#"[tag() = "kdl"]"#),
             Ok((
                 "",
-                Expression(Entity::TypeTag, Operator::Equal, "kdl".into())
+                Expression(
+                    Entity::TypeTag,
+                    Operator::Equal,
+                    None,
+                    "kdl".into(),
+                    Case::Sensitive
+                )
             ))
         );
         assert_eq!(
             matcher(r#"[props() = "kdl"]"#),
-            Ok(("", Expression(Entity::Props, Operator::Equal, "kdl".into())))
+            Ok((
+                "",
+                Expression(
+                    Entity::Props,
+                    Operator::Equal,
+                    None,
+                    "kdl".into(),
+                    Case::Sensitive
+                )
+            ))
         );
         assert_eq!(
             matcher(r#"[values() = "kdl"]"#),
             Ok((
                 "",
-                Expression(Entity::Values, Operator::Equal, "kdl".into())
+                Expression(
+                    Entity::Values,
+                    Operator::Equal,
+                    None,
+                    "kdl".into(),
+                    Case::Sensitive
+                )
             ))
         );
         assert_eq!(
             matcher(r#"[val() = 777]"#),
-            Ok(("", Expression(Entity::Val(0), Operator::Equal, 777.into())))
+            Ok((
+                "",
+                Expression(
+                    Entity::Val(0),
+                    Operator::Equal,
+                    None,
+                    777.into(),
+                    Case::Sensitive
+                )
+            ))
         );
         assert_eq!(
             matcher("[val(777) = 777]"),
             Ok((
                 "",
-                Expression(Entity::Val(777), Operator::Equal, 777.into())
+                Expression(
+                    Entity::Val(777),
+                    Operator::Equal,
+                    None,
+                    777.into(),
+                    Case::Sensitive
+                )
             ))
         );
         assert_eq!(
@@ -488,7 +1981,9 @@ mod tests {
                 Expression(
                     Entity::PropName("name".to_owned()),
                     Operator::Equal,
-                    777.into()
+                    None,
+                    777.into(),
+                    Case::Sensitive
                 )
             ))
         );
@@ -499,11 +1994,59 @@ mod tests {
                 Expression(
                     Entity::PropName("prop".to_owned()),
                     Operator::Equal,
-                    777.into()
+                    None,
+                    777.into(),
+                    Case::Sensitive
+                )
+            ))
+        );
+        assert_eq!(
+            matcher(r#"[val() = (date)"2021-01-01"]"#),
+            Ok((
+                "",
+                Expression(
+                    Entity::Val(0),
+                    Operator::Equal,
+                    Some("date".to_owned()),
+                    "2021-01-01".into(),
+                    Case::Sensitive
+                )
+            ))
+        );
+        assert_eq!(
+            matcher("[count(values()) >= 2]"),
+            Ok((
+                "",
+                Matcher::Count(
+                    Entity::Values,
+                    Operator::GreaterThanOrEqualTo,
+                    None,
+                    2.into()
+                )
+            ))
+        );
+        assert_eq!(
+            matcher("[count]"),
+            Ok(("", Direct(Entity::PropName("count".to_owned()))))
+        );
+        assert_eq!(
+            matcher(r#"[name() ^= "Win" i]"#),
+            Ok((
+                "",
+                Expression(
+                    Entity::NodeName,
+                    Operator::StartsWith,
+                    None,
+                    "Win".into(),
+                    Case::Insensitive
                 )
             ))
         );
         assert!(matcher("[some() = 777]").is_err());
+        assert_eq!(
+            matcher("[val() = 1 x]"),
+            Err(nom::Err::Failure(Error::expected(" x]", "`]`")))
+        );
     }
 
     #[test]
@@ -518,12 +2061,18 @@ mod tests {
         assert_eq!(entity("val(777)"), Ok(("", Val(777))));
         assert_eq!(
             entity("val(3.14)"),
-            Ok(("(3.14)", PropName("val".to_owned())))
+            Err(nom::Err::Failure(Error::expected(".14)", "`)`")))
+        );
+        assert_eq!(
+            entity("val(-0)"),
+            Err(nom::Err::Failure(Error::expected("-0)", "`)`")))
         );
-        assert_eq!(entity("val(-0)"), Ok(("(-0)", PropName("val".to_owned()))));
         assert_eq!(entity("prop(name)"), Ok(("", PropName("name".to_owned()))));
         assert_eq!(entity("prop"), Ok(("", PropName("prop".to_owned()))));
-        assert_eq!(entity("prop()"), Ok(("()", PropName("prop".to_owned()))));
+        assert_eq!(
+            entity("prop()"),
+            Err(nom::Err::Failure(Error::expected(")", "an identifier")))
+        );
         assert_eq!(entity("some()"), Ok(("()", PropName("some".to_owned()))));
 
         assert!(entity("0xEF").is_err());
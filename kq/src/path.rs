@@ -0,0 +1,50 @@
+use crate::tree::{Position, Tree};
+use kdl::{KdlIdentifier, KdlNode};
+use std::fmt;
+
+/// Where a node is in a document: the names of its ancestors and of the node itself,
+/// each with its index among the preceding siblings of the same name.
+///
+/// It is printed as `package[0]/dependencies[1]/miette[0]`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Path(Vec<(String, usize)>);
+
+impl Path {
+    pub fn segments(&self) -> &[(String, usize)] {
+        &self.0
+    }
+}
+
+/// Names that are not valid bare identifiers, e.g. ones containing `/`, are quoted.
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, index)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "/")?;
+            }
+            write!(f, "{}[{}]", KdlIdentifier::from(name.as_str()), index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Pairs every matched node with its path.
+pub(crate) fn locate<'a>(matched: Vec<&'a KdlNode>, tree: &Tree<'a>) -> Vec<(Path, &'a KdlNode)> {
+    matched
+        .into_iter()
+        .filter_map(|node| tree.lineage(node).map(|lineage| (path(&lineage), node)))
+        .collect()
+}
+
+/// The path of the node at the end of `lineage`, see [`Tree::lineage`].
+fn path(lineage: &[Position]) -> Path {
+    let segments = lineage.iter().map(|position| {
+        let name = position.node().name().value();
+        let index = position.siblings[..position.index]
+            .iter()
+            .filter(|sibling| sibling.name().value() == name)
+            .count();
+        (name.to_owned(), index)
+    });
+    Path(segments.collect())
+}
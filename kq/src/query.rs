@@ -0,0 +1,112 @@
+use crate::parser::{self, Clause, Combinator, Mapping, Update};
+use crate::path::{self, Path};
+use crate::tree::Tree;
+use crate::{map_by_mapping, parse, query_by_alternation, Error, Output};
+use kdl::KdlNode;
+
+/// A parsed query, which can be applied to any number of documents without parsing it again.
+///
+/// ```
+/// let query = kq::Query::parse("dependencies > [] => name()")?;
+/// let document = "dependencies { miette; kdl; }".parse::<kdl::KdlDocument>()?;
+/// let outputs = query.apply(document.nodes());
+///
+/// assert_eq!(outputs.len(), 2);
+/// assert_eq!(outputs[0].to_string(), r#""miette""#);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    pub(crate) alternation: Vec<Vec<Combinator>>,
+    /// The map operator, with the byte offset of its accessor in the query.
+    pub(crate) mapping: Option<(usize, Mapping)>,
+    /// The assignments of the update operator, if any.
+    pub(crate) update: Update,
+}
+
+impl Query {
+    /// Parses a selector, optionally followed by the map operator (`selector => accessor`)
+    /// or the update operator (`selector |= prop(dev) = false`).
+    /// An empty selector matches the top-level nodes of a document.
+    pub fn parse(input: &str) -> Result<Query, Error> {
+        if input.trim().is_empty() {
+            Ok(Query {
+                alternation: vec![],
+                mapping: None,
+                update: vec![],
+            })
+        } else {
+            parse(input, parser::query).map(|(alternation, clause)| match clause {
+                Some(Clause::Mapping(rest, mapping)) => Query {
+                    alternation,
+                    mapping: Some((input.len() - rest.len(), mapping)),
+                    update: vec![],
+                },
+                Some(Clause::Update(update)) => Query {
+                    alternation,
+                    mapping: None,
+                    update,
+                },
+                None => Query {
+                    alternation,
+                    mapping: None,
+                    update: vec![],
+                },
+            })
+        }
+    }
+
+    /// Makes every matcher of the query compare strings regardless of case, as if each of them
+    /// had the `i` modifier, e.g. `[name() = "windows" i]`, and so do node names and type
+    /// annotations, e.g. `windows` matches `Windows 1`.
+    pub fn ignore_case(mut self) -> Query {
+        self.alternation
+            .iter_mut()
+            .flatten()
+            .for_each(Combinator::ignore_case);
+        self
+    }
+
+    /// Evaluates the query against `document`, the matched nodes are cloned, or projected
+    /// into values when the query has a map operator.
+    pub fn apply(&self, document: &[KdlNode]) -> Vec<Output> {
+        self.select(document)
+            .into_iter()
+            .map(|node| self.project(node))
+            .collect()
+    }
+
+    /// Projects a node matched by [`Query::select`] the way [`Query::apply`] does.
+    pub fn project(&self, node: &KdlNode) -> Output {
+        match &self.mapping {
+            Some((_offset, mapping)) => map_by_mapping(mapping, node),
+            None => Output::Node(node.clone()),
+        }
+    }
+
+    /// Tells whether the query has a map operator, see [`Query::project`].
+    pub fn has_mapping(&self) -> bool {
+        self.mapping.is_some()
+    }
+
+    /// Evaluates the selector of the query against `document`, and borrows the matched nodes
+    /// from it. The map operator, if any, is not applied.
+    pub fn select<'a>(&self, document: &'a [KdlNode]) -> Vec<&'a KdlNode> {
+        self.select_in(&Tree::new(document))
+    }
+
+    /// Like [`Query::select`], but also tells where every matched node is in `document`.
+    pub fn select_with_paths<'a>(&self, document: &'a [KdlNode]) -> Vec<(Path, &'a KdlNode)> {
+        let tree = Tree::new(document);
+        path::locate(self.select_in(&tree), &tree)
+    }
+
+    /// Like [`Query::select`], with the positions of `tree` left for the caller to reuse.
+    pub(crate) fn select_in<'a>(&self, tree: &Tree<'a>) -> Vec<&'a KdlNode> {
+        if self.alternation.is_empty() {
+            tree.document().iter().collect()
+        } else {
+            query_by_alternation(&self.alternation, tree)
+        }
+    }
+}
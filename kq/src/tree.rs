@@ -0,0 +1,104 @@
+use crate::children;
+use kdl::KdlNode;
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::iter;
+
+/// Where the nodes of a document are, which the nodes themselves do not know, e.g. whether
+/// a node is the first child of its parent for `[]:first-child`.
+///
+/// The positions are only collected, in one walk over the document, once they are asked for.
+pub(crate) struct Tree<'a> {
+    document: &'a [KdlNode],
+    positions: OnceCell<HashMap<*const KdlNode, Position<'a>>>,
+}
+
+/// A node among its siblings, the nodes of the same parent, or of the document itself.
+#[derive(Clone, Copy)]
+pub(crate) struct Position<'a> {
+    pub(crate) parent: Option<&'a KdlNode>,
+    pub(crate) siblings: &'a [KdlNode],
+    pub(crate) index: usize,
+    /// Where the node comes in the document, counting every node before its children.
+    pub(crate) order: usize,
+}
+
+impl<'a> Position<'a> {
+    /// The node itself, borrowed for as long as the document.
+    pub(crate) fn node(&self) -> &'a KdlNode {
+        &self.siblings[self.index]
+    }
+}
+
+impl<'a> Tree<'a> {
+    pub(crate) fn new(document: &'a [KdlNode]) -> Self {
+        Tree {
+            document,
+            positions: OnceCell::new(),
+        }
+    }
+
+    pub(crate) fn document(&self) -> &'a [KdlNode] {
+        self.document
+    }
+
+    /// The position of `node`, or `None` when it is not part of the document.
+    pub(crate) fn position(&self, node: &KdlNode) -> Option<Position<'a>> {
+        self.positions
+            .get_or_init(|| {
+                let mut positions = HashMap::new();
+                collect_positions(None, self.document, &mut positions);
+                positions
+            })
+            .get(&(node as *const KdlNode))
+            .copied()
+    }
+
+    /// The parent of `node`, or `None` when it is a top-level node.
+    pub(crate) fn parent(&self, node: &KdlNode) -> Option<&'a KdlNode> {
+        self.position(node).and_then(|position| position.parent)
+    }
+
+    /// The positions of the top-level ancestor of `node` down to `node` itself, or `None` when
+    /// it is not part of the document.
+    pub(crate) fn lineage(&self, node: &KdlNode) -> Option<Vec<Position<'a>>> {
+        let position = self.position(node)?;
+        let mut lineage = iter::successors(Some(position), |position| {
+            position.parent.and_then(|parent| self.position(parent))
+        })
+        .collect::<Vec<_>>();
+        lineage.reverse();
+        Some(lineage)
+    }
+
+    /// `nodes` in the order they appear in the document, without duplicates.
+    pub(crate) fn in_document_order(
+        &self,
+        nodes: impl IntoIterator<Item = &'a KdlNode>,
+    ) -> Vec<&'a KdlNode> {
+        let mut positions = nodes
+            .into_iter()
+            .filter_map(|node| self.position(node))
+            .collect::<Vec<_>>();
+        positions.sort_by_key(|position| position.order);
+        positions.dedup_by_key(|position| position.order);
+        positions.iter().map(Position::node).collect()
+    }
+}
+
+fn collect_positions<'a>(
+    parent: Option<&'a KdlNode>,
+    siblings: &'a [KdlNode],
+    positions: &mut HashMap<*const KdlNode, Position<'a>>,
+) {
+    for (index, node) in siblings.iter().enumerate() {
+        let position = Position {
+            parent,
+            siblings,
+            index,
+            order: positions.len(),
+        };
+        positions.insert(node as *const KdlNode, position);
+        collect_positions(Some(node), children(node), positions);
+    }
+}
@@ -35,6 +35,23 @@ fn top_descendant_any_element() {
                     }
                 }
             }
+            fmt_and_docs "Check fmt & build docs"
+            build_and_test "Build & Test" {
+                strategy {
+                    matrix {
+                        os "ubuntu-latest" "macOS-latest" "windows-latest"
+                    }
+                }
+            }
+            strategy {
+                matrix {
+                    os "ubuntu-latest" "macOS-latest" "windows-latest"
+                }
+            }
+            matrix {
+                os "ubuntu-latest" "macOS-latest" "windows-latest"
+            }
+            os "ubuntu-latest" "macOS-latest" "windows-latest"
         "#});
 }
 
@@ -113,7 +130,7 @@ fn adjacent_sibling() {
                 li "Maintainability"
                 li "Flexibility"
                 li "Cognitive simplicity and Learnability"
-                li "Ease of de\/serialization"
+                li "Ease of de/serialization"
                 li "Ease of implementation"
             }
         "#});
@@ -128,7 +145,7 @@ fn general_adjacent_siblings() {
         .assert()
         .success()
         .stdout(predicates::str::starts_with("link"))
-        .stdout(predicates::str::contains(r#"href="\/styles\/global.css""#))
+        .stdout(predicates::str::contains(r#"href="/styles/global.css""#))
         .stdout(predicates::str::contains(r#"rel="stylesheet""#));
 }
 
@@ -141,7 +158,7 @@ fn adjacent_general_siblings() {
         .assert()
         .success()
         .stdout(predicates::str::starts_with("link"))
-        .stdout(predicates::str::contains(r#"href="\/styles\/global.css""#))
+        .stdout(predicates::str::contains(r#"href="/styles/global.css""#))
         .stdout(predicates::str::contains(r#"rel="stylesheet""#));
 }
 
@@ -170,7 +187,7 @@ fn complex_nested() {
             li "Maintainability"
             li "Flexibility"
             li "Cognitive simplicity and Learnability"
-            li "Ease of de\/serialization"
+            li "Ease of de/serialization"
             li "Ease of implementation"
         "#});
 }
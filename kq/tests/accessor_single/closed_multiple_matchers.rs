@@ -0,0 +1,104 @@
+use assert_cmd::Command;
+use indoc::indoc;
+
+#[test]
+fn all_present() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[val()][prop(name)]")
+        .write_stdin(indoc! {r#"
+            package "kq" name="kq"
+            package "kdl"
+            package name="nom"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            package "kq" name="kq"
+        "#});
+}
+
+#[test]
+fn present_and_expression() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"package[val()][name = "foo"]"#)
+        .write_stdin(indoc! {r#"
+            package "1.0.0" name="foo"
+            package name="foo"
+            package "2.0.0" name="bar"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            package "1.0.0" name="foo"
+        "#});
+}
+
+#[test]
+fn expressions() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[val() >= 1][val() < 3]")
+        .write_stdin(indoc! {r#"
+            a 0
+            b 1
+            c 2
+            d 3
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            b 1
+            c 2
+        "#});
+}
+
+#[test]
+fn none_satisfied() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"package[name = "foo"][name = "bar"]"#)
+        .write_stdin(indoc! {r#"
+            package name="foo"
+            package name="bar"
+        "#})
+        .assert()
+        .success()
+        .stdout(predicates::str::is_empty());
+}
+
+#[test]
+fn child_combinator() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("top() > [val()][dev = true]")
+        .write_stdin(indoc! {r#"
+            winapi "1.0.0" dev=false
+            miette "2.0.0" dev=true
+            nom dev=true
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            miette "2.0.0" dev=true
+        "#});
+}
+
+#[test]
+fn sibling_combinator() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[val()][dev] + []")
+        .write_stdin(indoc! {r#"
+            winapi "1.0.0"
+            nom dev=true
+            miette "2.0.0" dev=true
+            kdl "3.0.0"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            kdl "3.0.0"
+        "#});
+}
@@ -134,7 +134,14 @@ fn greater_than() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+            step "Install Rust" uses="actions-rs/toolchain@v1" {
+                profile "minimal"
+            }
+            step "Clippy" run="cargo clippy --all -- -D warnings"
+            step "Run tests" run="cargo test --all --verbose"
+        "#});
 }
 
 #[test]
@@ -152,7 +159,15 @@ fn greater_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+            step "Install Rust" uses="actions-rs/toolchain@v1" {
+                profile "minimal"
+            }
+            step "Clippy" run="cargo clippy --all -- -D warnings"
+            step "Run tests" run="cargo test --all --verbose"
+            profile "minimal"
+        "#});
 }
 
 #[test]
@@ -188,5 +203,43 @@ fn less_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            profile "minimal"
+        "#});
+}
+
+#[test]
+fn matches() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"[name() ~= "^pro"]"#)
+        .write_stdin(indoc! {r#"
+            step uses="actions/checkout@v1"
+            step "Install Rust" uses="actions-rs/toolchain@v1" {
+                profile "minimal"
+            }
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            profile "minimal"
+        "#});
+}
+
+#[test]
+fn equal_ignoring_case() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"[name() = "STEP" i]"#)
+        .write_stdin(indoc! {r#"
+            step uses="actions/checkout@v1"
+            Step "Install Rust" uses="actions-rs/toolchain@v1"
+            profile "minimal"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+            Step "Install Rust" uses="actions-rs/toolchain@v1"
+        "#});
 }
@@ -14,8 +14,8 @@ fn present() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -47,7 +47,7 @@ fn equal() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
+            step uses="actions/checkout@v1"
         "#});
 }
 
@@ -64,7 +64,7 @@ fn not_equal() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -81,8 +81,8 @@ fn starts_with() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -99,8 +99,8 @@ fn ends_with() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -117,7 +117,7 @@ fn contains() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -148,7 +148,9 @@ fn greater_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+        "#});
 }
 
 #[test]
@@ -163,7 +165,9 @@ fn less_than() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions-rs/toolchain@v1"
+        "#});
 }
 
 #[test]
@@ -178,5 +182,62 @@ fn less_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
+        "#});
+}
+
+#[test]
+fn matches() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"[prop(uses) ~= r"^actions/\w+@v1$"]"#)
+        .write_stdin(indoc! {r#"
+            step uses="actions/checkout@v1"
+            step run="cargo test --all --verbose"
+            step uses="actions-rs/toolchain@v1"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+        "#});
+}
+
+#[test]
+fn starts_with_ignoring_case() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"[prop(uses) ^= "ACTIONS/" i]"#)
+        .write_stdin(indoc! {r#"
+            step uses="actions/checkout@v1"
+            step uses="Actions/Cache@v2"
+            step uses="actions-rs/toolchain@v1"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+            step uses="Actions/Cache@v2"
+        "#});
+}
+
+#[test]
+fn ignore_case_option() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("--ignore-case")
+        .arg(r#"[prop(uses) ~= "^actions/cache"] || [prop(run) *= "TEST"]"#)
+        .write_stdin(indoc! {r#"
+            step uses="actions/checkout@v1"
+            step uses="Actions/Cache@v2"
+            step run="cargo test --all --verbose"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            step uses="Actions/Cache@v2"
+            step run="cargo test --all --verbose"
+        "#});
 }
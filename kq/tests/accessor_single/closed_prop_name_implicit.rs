@@ -13,7 +13,7 @@ fn present() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
+            step uses="actions/checkout@v1"
         "#});
 }
 
@@ -44,7 +44,7 @@ fn equal() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
+            step uses="actions/checkout@v1"
         "#});
 }
 
@@ -61,7 +61,7 @@ fn not_equal() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -78,8 +78,8 @@ fn starts_with() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -96,8 +96,8 @@ fn ends_with() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -114,8 +114,8 @@ fn contains() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -146,7 +146,9 @@ fn greater_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+        "#});
 }
 
 #[test]
@@ -161,7 +163,9 @@ fn less_than() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions-rs/toolchain@v1"
+        "#});
 }
 
 #[test]
@@ -176,5 +180,8 @@ fn less_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
+        "#});
 }
@@ -16,7 +16,15 @@ fn parentheses() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+            step "Install Rust" uses="actions-rs/toolchain@v1" {
+                props "props" props="props"
+            }
+            step "Clippy" run="cargo clippy --all -- -D warnings"
+            step "Run tests" run="cargo test --all --verbose"
+            props "props" props="props"
+        "#});
 }
 
 #[test]
@@ -44,7 +52,10 @@ fn not_equal() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step (ascii)"Clippy" run="cargo clippy --all -- -D warnings"
+            step "Run tests" run="cargo test --all --verbose"
+        "#});
 }
 
 #[test]
@@ -100,7 +111,10 @@ fn greater_than() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step (ascii)"Clippy" run="cargo clippy --all -- -D warnings"
+            step "Run tests" run="cargo test --all --verbose"
+        "#});
 }
 
 #[test]
@@ -114,7 +128,10 @@ fn greater_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step (ascii)"Clippy" run="cargo clippy --all -- -D warnings"
+            step "Run tests" run="cargo test --all --verbose"
+        "#});
 }
 
 #[test]
@@ -144,3 +161,20 @@ fn less_than_or_equal_to() {
         .success()
         .stdout(predicates::str::is_empty());
 }
+
+#[test]
+fn count() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[count(props()) = 0]")
+        .write_stdin(indoc! {r#"
+            step uses="actions/checkout@v1"
+            step "Clippy"
+            step "Run tests" run="cargo test --all --verbose"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            step "Clippy"
+        "#});
+}
@@ -140,3 +140,52 @@ fn less_than_or_equal_to() {
         .success()
         .stdout(predicates::str::is_empty());
 }
+
+#[test]
+fn present_on_node() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[tag()]")
+        .write_stdin(indoc! {r#"
+            (lint)step "Clippy" run="cargo clippy --all -- -D warnings"
+            step "Run tests" run="cargo test --all --verbose"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            (lint)step "Clippy" run="cargo clippy --all -- -D warnings"
+        "#});
+}
+
+#[test]
+fn equal_on_node() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"[tag() = "date"]"#)
+        .write_stdin(indoc! {r#"
+            (date)released "2021-01-01"
+            (time)released "12:00"
+            released "2021-01-01"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            (date)released "2021-01-01"
+        "#});
+}
+
+#[test]
+fn starts_with_on_node() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"[tag() ^= "da"]"#)
+        .write_stdin(indoc! {r#"
+            (date)released "2021-01-01"
+            (time)released "12:00"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            (date)released "2021-01-01"
+        "#});
+}
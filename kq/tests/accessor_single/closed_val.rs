@@ -163,7 +163,9 @@ fn greater_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            on "push" "pull_request"
+        "#});
 }
 
 #[test]
@@ -178,7 +180,9 @@ fn less_than() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            name "CI"
+        "#});
 }
 
 #[test]
@@ -193,5 +197,150 @@ fn less_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            on "push" "pull_request"
+            name "CI"
+        "#});
+}
+
+#[test]
+fn equal_with_type_tag() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"[val() = (date)"2021-01-01"]"#)
+        .write_stdin(indoc! {r#"
+            released (date)"2021-01-01"
+            updated (time)"2021-01-01"
+            created "2021-01-01"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            released (date)"2021-01-01"
+        "#});
+}
+
+#[test]
+fn equal_ignores_type_tag() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"[val() = "2021-01-01"]"#)
+        .write_stdin(indoc! {r#"
+            released (date)"2021-01-01"
+            created "2021-01-01"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            released (date)"2021-01-01"
+            created "2021-01-01"
+        "#});
+}
+
+#[test]
+fn greater_than_float() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[val() > 1.5]")
+        .write_stdin(indoc! {r#"
+            timeout 3
+            retries 1
+            ratio 1.75
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            timeout 3
+            ratio 1.75
+        "#});
+}
+
+#[test]
+fn equal_across_int_and_float() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[val() = 2]")
+        .write_stdin(indoc! {r#"
+            width 2.0
+            height 0x2
+            depth 2.5
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            width 2.0
+            height 0x2
+        "#});
+}
+
+#[test]
+fn less_than_or_equal_to_int() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[val() <= 2]")
+        .write_stdin(indoc! {r#"
+            width 2.0
+            height 2.5
+            depth -0.5
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            width 2.0
+            depth -0.5
+        "#});
+}
+
+#[test]
+fn greater_than_or_equal_to_semver() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"[val() >= (semver)"1.2.0"]"#)
+        .write_stdin(indoc! {r#"
+            miette "1.10.0"
+            nom "1.2.0-rc.1"
+            kdl (semver)"1.2.0"
+            serde "latest"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            miette "1.10.0"
+            kdl (semver)"1.2.0"
+        "#});
+}
+
+#[test]
+fn less_than_string() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"[val() < "2022-01-01"]"#)
+        .write_stdin(indoc! {r#"
+            released "2021-12-31"
+            updated "2022-03-01"
+            tagged "1.10.0"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            released "2021-12-31"
+            tagged "1.10.0"
+        "#});
+}
+
+#[test]
+fn matches() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"[val(0) ~= "^p.*h$"]"#)
+        .write_stdin(indoc! {r#"
+            on "push" "pull_request"
+            name "CI"
+            jobs
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            on "push" "pull_request"
+        "#});
 }
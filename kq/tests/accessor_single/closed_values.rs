@@ -16,7 +16,14 @@ fn parentheses() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step "Install Rust" uses="actions-rs/toolchain@v1" {
+                profile "minimal"
+            }
+            step "Clippy" run="cargo clippy --all -- -D warnings"
+            step "Run tests" run="cargo test --all --verbose"
+            profile "minimal"
+        "#});
 }
 
 #[test]
@@ -44,7 +51,10 @@ fn not_equal() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step (ascii)"Clippy" run="cargo clippy --all -- -D warnings"
+            step "Run tests" run="cargo test --all --verbose"
+        "#});
 }
 
 #[test]
@@ -128,7 +138,10 @@ fn less_than() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step (ascii)"Clippy" run="cargo clippy --all -- -D warnings"
+            step "Run tests" run="cargo test --all --verbose"
+        "#});
 }
 
 #[test]
@@ -142,5 +155,26 @@ fn less_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step (ascii)"Clippy" run="cargo clippy --all -- -D warnings"
+            step "Run tests" run="cargo test --all --verbose"
+        "#});
+}
+
+#[test]
+fn count() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[count(values()) >= 2]")
+        .write_stdin(indoc! {r#"
+            step "Install Rust" "stable" uses="actions-rs/toolchain@v1"
+            step "Clippy" run="cargo clippy --all -- -D warnings"
+            step "Run tests" "--all" "--verbose"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            step "Install Rust" "stable" uses="actions-rs/toolchain@v1"
+            step "Run tests" "--all" "--verbose"
+        "#});
 }
@@ -260,7 +260,9 @@ fn greater_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            profile "minimal"
+        "#});
 }
 
 #[test]
@@ -332,7 +334,9 @@ fn less_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            profile "minimal"
+        "#});
 }
 
 #[test]
@@ -14,8 +14,8 @@ fn present_prop() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -62,7 +62,7 @@ fn equal() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
+            step uses="actions/checkout@v1"
         "#});
 }
 
@@ -94,7 +94,7 @@ fn not_equal() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -126,8 +126,8 @@ fn starts_with() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -159,8 +159,8 @@ fn ends_with() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -192,7 +192,7 @@ fn contains() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -253,7 +253,9 @@ fn greater_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+        "#});
 }
 
 #[test]
@@ -283,7 +285,9 @@ fn less_than() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions-rs/toolchain@v1"
+        "#});
 }
 
 #[test]
@@ -313,7 +317,10 @@ fn less_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
+        "#});
 }
 
 #[test]
@@ -13,7 +13,7 @@ fn present_prop() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
+            step uses="actions/checkout@v1"
         "#});
 }
 
@@ -58,7 +58,7 @@ fn equal() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
+            step uses="actions/checkout@v1"
         "#});
 }
 
@@ -90,7 +90,7 @@ fn not_equal() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -122,8 +122,8 @@ fn starts_with() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -155,8 +155,8 @@ fn ends_with() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -188,8 +188,8 @@ fn contains() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            step uses="actions\/checkout@v1"
-            step uses="actions-rs\/toolchain@v1"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
         "#});
 }
 
@@ -250,7 +250,9 @@ fn greater_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+        "#});
 }
 
 #[test]
@@ -280,7 +282,9 @@ fn less_than() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions-rs/toolchain@v1"
+        "#});
 }
 
 #[test]
@@ -310,7 +314,10 @@ fn less_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            step uses="actions/checkout@v1"
+            step uses="actions-rs/toolchain@v1"
+        "#});
 }
 
 #[test]
@@ -297,7 +297,9 @@ fn greater_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            on "push" "pull_request"
+        "#});
 }
 
 #[test]
@@ -327,7 +329,9 @@ fn less_than() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            name "CI"
+        "#});
 }
 
 #[test]
@@ -357,7 +361,10 @@ fn less_than_or_equal_to() {
         "#})
         .assert()
         .success()
-        .stdout(predicates::str::is_empty());
+        .stdout(indoc! {r#"
+            on "push" "pull_request"
+            name "CI"
+        "#});
 }
 
 #[test]
@@ -1,6 +1,7 @@
 use assert_cmd::Command;
 use indoc::indoc;
 
+mod closed_multiple_matchers;
 mod closed_node_name;
 mod closed_prop_name_explicit;
 mod closed_prop_name_implicit;
@@ -77,6 +78,50 @@ fn type_type_with_identifier() {
         .stdout(predicates::str::is_empty());
 }
 
+#[test]
+fn type_tag_present() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("()")
+        .write_stdin(indoc! {r#"
+            (author)name "Kat"
+            (ci)jobs {
+                (step)fmt_and_docs "Check fmt & build docs"
+            }
+            version (semver)"1.0.0"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            (author)name "Kat"
+            (ci)jobs {
+                (step)fmt_and_docs "Check fmt & build docs"
+            }
+            (step)fmt_and_docs "Check fmt & build docs"
+        "#});
+}
+
+#[test]
+fn type_tag_with_identifier_present() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("(author)")
+        .write_stdin(indoc! {r#"
+            (author)name "Kat"
+            contributors {
+                (author)name "Jane"
+                (maintainer)name "John"
+            }
+            version (author)"1.0.0"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            (author)name "Kat"
+            (author)name "Jane"
+        "#});
+}
+
 #[test]
 fn sole() {
     Command::cargo_bin("kq")
@@ -122,5 +167,41 @@ fn any_element() {
                 fmt_and_docs "Check fmt & build docs"
                 build_and_test "Build & Test"
             }
+            fmt_and_docs "Check fmt & build docs"
+            build_and_test "Build & Test"
+        "#});
+}
+
+#[test]
+fn ignore_case_name() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["--ignore-case", "windows"])
+        .write_stdin(indoc! {r#"
+            Windows 1
+            linux 2
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            Windows 1
+        "#});
+}
+
+#[test]
+fn ignore_case_type_tag_and_name() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["--ignore-case", "(DEV) || MIETTE[val()]"])
+        .write_stdin(indoc! {r#"
+            (dev)winapi "1.0.0"
+            miette "2.0.0"
+            kdl "4.0.0"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            (dev)winapi "1.0.0"
+            miette "2.0.0"
         "#});
 }
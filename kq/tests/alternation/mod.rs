@@ -0,0 +1,126 @@
+use assert_cmd::Command;
+use indoc::indoc;
+
+const INPUT: &str = indoc! {r#"
+    package {
+        name "foo"
+        version "1.0.0"
+        dependencies platform="windows" {
+            winapi "1.0.0" path="./crates/my-winapi-fork"
+        }
+        dev-dependencies {
+            miette "2.0.0"
+        }
+    }
+"#};
+
+#[test]
+fn union() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies || dev-dependencies")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            dependencies platform="windows" {
+                winapi "1.0.0" path="./crates/my-winapi-fork"
+            }
+            dev-dependencies {
+                miette "2.0.0"
+            }
+        "#});
+}
+
+#[test]
+fn document_order() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("miette || package > name || winapi")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            name "foo"
+            winapi "1.0.0" path="./crates/my-winapi-fork"
+            miette "2.0.0"
+        "#});
+}
+
+#[test]
+fn without_duplicates() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("package > [] || [val() = \"1.0.0\"]")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            name "foo"
+            version "1.0.0"
+            dependencies platform="windows" {
+                winapi "1.0.0" path="./crates/my-winapi-fork"
+            }
+            winapi "1.0.0" path="./crates/my-winapi-fork"
+            dev-dependencies {
+                miette "2.0.0"
+            }
+        "#});
+}
+
+#[test]
+fn document_order_with_empty_branch() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("package [] || build-dependencies")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            name "foo"
+            version "1.0.0"
+            dependencies platform="windows" {
+                winapi "1.0.0" path="./crates/my-winapi-fork"
+            }
+            winapi "1.0.0" path="./crates/my-winapi-fork"
+            dev-dependencies {
+                miette "2.0.0"
+            }
+            miette "2.0.0"
+        "#});
+}
+
+#[test]
+fn no_match() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("build-dependencies || target")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(predicates::str::is_empty());
+}
+
+#[test]
+fn with_map_operator() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies > [] || dev-dependencies > [] => name()")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "winapi"
+            "miette"
+        "#});
+}
+
+#[test]
+fn missing_selector() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies ||")
+        .write_stdin(INPUT)
+        .assert()
+        .failure();
+}
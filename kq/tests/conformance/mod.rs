@@ -0,0 +1,303 @@
+// the selectors of https://github.com/kdl-org/kdl/blob/1.0.0/QUERY-SPEC.md#selectors, on a
+// document where the nodes of the same name are told apart by their argument
+use assert_cmd::Command;
+use indoc::indoc;
+
+const INPUT: &str = indoc! {r#"
+    a 1 {
+        b 1
+        a 2 {
+            b 2
+        }
+        c 1
+    }
+    b 3
+    c 2
+"#};
+
+#[test]
+fn top() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("top() => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "a" 1
+            "b" 3
+            "c" 2
+        "#});
+}
+
+#[test]
+fn top_child_any_element() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("top() > [] => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "a" 1
+            "b" 3
+            "c" 2
+        "#});
+}
+
+#[test]
+fn top_child() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("top() > b => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "b" 3
+        "#});
+}
+
+#[test]
+fn top_child_matcher() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("top() > [name() = \"b\"] => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "b" 3
+        "#});
+}
+
+#[test]
+fn top_descendant() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("top() b => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "b" 3
+            "b" 1
+            "b" 2
+        "#});
+}
+
+#[test]
+fn any_element() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[] => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "a" 1
+            "b" 3
+            "c" 2
+            "b" 1
+            "a" 2
+            "c" 1
+            "b" 2
+        "#});
+}
+
+#[test]
+fn child() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("a > b => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "b" 1
+            "b" 2
+        "#});
+}
+
+#[test]
+fn child_matcher() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("a > [name() = \"b\"] => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "b" 1
+            "b" 2
+        "#});
+}
+
+#[test]
+fn parent_then_child_matcher() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("b parent() > [val() = 1] => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "b" 1
+            "c" 1
+        "#});
+}
+
+#[test]
+fn descendant() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("a b => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "b" 1
+            "b" 2
+        "#});
+}
+
+#[test]
+fn descendant_of_same_name() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("a a => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "a" 2
+        "#});
+}
+
+#[test]
+fn descendant_not_self() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("b b => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn descendant_deduplicated() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("a [] => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "b" 1
+            "a" 2
+            "c" 1
+            "b" 2
+        "#});
+}
+
+#[test]
+fn adjacent_sibling() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("a + b => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "b" 3
+        "#});
+}
+
+#[test]
+fn general_sibling() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("b ~ c => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "c" 2
+            "c" 1
+        "#});
+}
+
+#[test]
+fn has_child_matcher() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("a:has(> [name() = \"b\"]) => (name(), val())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "a" 1
+            "a" 2
+        "#});
+}
+
+#[test]
+fn top_after_descendant() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("a top()")
+        .write_stdin(INPUT)
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn top_after_child() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("a > top()")
+        .write_stdin(INPUT)
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn sibling_after_top() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("top() + b")
+        .write_stdin(INPUT)
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn parent_after_top() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("top() parent()")
+        .write_stdin(INPUT)
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn top_in_has() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[]:has(top() > b)")
+        .write_stdin(INPUT)
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn top_in_not() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[]:not(top())")
+        .write_stdin(INPUT)
+        .assert()
+        .code(3);
+}
@@ -3,6 +3,19 @@ use predicates::prelude::*;
 
 mod accessor_multiple;
 mod accessor_single;
+mod alternation;
+mod conformance;
+mod edit;
+mod error;
+mod exit_status;
+mod input;
+mod location;
+mod map_operator;
+mod navigation;
+mod output_format;
+mod paths;
+mod pseudo_class;
+mod update;
 
 #[test]
 fn sanity() {
@@ -0,0 +1,343 @@
+use assert_cmd::Command;
+use indoc::indoc;
+use std::env;
+use std::fs;
+
+const PACKAGE: &str = indoc! {r#"
+    // the package
+    package {
+        name "kq"  // the name
+        version "1.0.0"
+        dependencies {
+            nom "7" optional=false
+            kdl 0x4
+        }
+    }
+    "#};
+
+#[test]
+fn set() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package > version", "--set", "--value", r#""2.0.0""#])
+        .write_stdin(PACKAGE)
+        .assert()
+        .success()
+        .stdout(PACKAGE.replace(r#""1.0.0""#, r#""2.0.0""#));
+}
+
+#[test]
+fn set_keeps_representation() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["dependencies > kdl", "--set", "--value", "0x5"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .success()
+        .stdout(PACKAGE.replace("kdl 0x4", "kdl 0x5"));
+}
+
+#[test]
+fn set_property() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["nom => prop(optional)", "--set", "--value", "true"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .success()
+        .stdout(PACKAGE.replace("optional=false", "optional=true"));
+}
+
+#[test]
+fn delete() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package > name", "--delete"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            // the package
+            package {
+                version "1.0.0"
+                dependencies {
+                    nom "7" optional=false
+                    kdl 0x4
+                }
+            }
+        "#});
+}
+
+#[test]
+fn delete_every_child() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["dependencies > []", "--delete"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            // the package
+            package {
+                name "kq"  // the name
+                version "1.0.0"
+                dependencies {}
+            }
+        "#});
+}
+
+#[test]
+fn delete_property() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["nom => prop(optional)", "--delete"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .success()
+        .stdout(PACKAGE.replace(r#"nom "7" optional=false"#, r#"nom "7""#));
+}
+
+#[test]
+fn rename() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package > dependencies", "--rename", "dev-dependencies"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .success()
+        .stdout(PACKAGE.replace("dependencies {", "dev-dependencies {"));
+}
+
+#[test]
+fn no_match() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-e", "workspace", "--delete"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(1)
+        .stdout(PACKAGE);
+}
+
+#[test]
+fn in_place() {
+    let path = env::temp_dir().join(format!("kq-edit-in-place-{}.kdl", std::process::id()));
+    fs::write(&path, PACKAGE).unwrap();
+
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package > version", "--set", "--value", r#""2.0.0""#, "-i"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout("");
+
+    let edited = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(edited, PACKAGE.replace(r#""1.0.0""#, r#""2.0.0""#));
+}
+
+#[test]
+fn in_place_from_stdin() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package", "--delete", "--in-place"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(2)
+        .stderr("Error: --in-place can only edit files, not the standard input\n");
+}
+
+#[test]
+fn in_place_without_edit() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package", "-i"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(2)
+        .stderr(
+            "Error: --in-place requires --set, --delete, --rename or the update operator `|=`\n",
+        );
+}
+
+#[test]
+fn delete_with_json_output() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package", "--delete", "-o", "json"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(
+            "Error: --output json or jsonl can not be combined with --set, --delete, --rename or the update operator `|=`\n",
+        );
+}
+
+#[test]
+fn delete_with_paths() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package", "--delete", "-p"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(
+            "Error: --paths can not be combined with --set, --delete, --rename or the update operator `|=`\n",
+        );
+}
+
+#[test]
+fn delete_with_filename() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package", "--delete", "-H"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(
+            "Error: --with-filename can not be combined with --set, --delete, --rename or the update operator `|=`\n",
+        );
+}
+
+#[test]
+fn delete_with_location() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package", "--delete", "--with-location"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(
+            "Error: --with-location can not be combined with --set, --delete, --rename or the update operator `|=`\n",
+        );
+}
+
+#[test]
+fn update_with_paths() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-p", "package |= del(version)"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(2)
+        .stderr(
+            "Error: --paths can not be combined with --set, --delete, --rename or the update operator `|=`\n",
+        );
+}
+
+#[test]
+fn set_without_value() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package", "--set"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(2)
+        .stderr("Error: --set requires a --value\n");
+}
+
+#[test]
+fn several_edits() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package", "--delete", "--rename", "workspace"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(2)
+        .stderr("Error: only one of --set, --delete and --rename can be given\n");
+}
+
+#[test]
+fn invalid_value() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package", "--set", "--value", "1 2"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(2)
+        .stderr("Error: invalid value: 1 2\n");
+}
+
+#[test]
+fn unsupported_edit() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package => name()", "--delete"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(3)
+        .stderr(indoc! {"
+            Error: deleting `name()` is not supported
+                package => name()
+                           ^
+        "});
+}
+
+#[test]
+fn keep_formatting() {
+    let document = "package {\r\n    size 0xFF   // hex\r\n    count 1_000\r\n\r\n    /- old \"x\"\r\n    name \"a\" /-\"b\" c=1\r\n}\r\n";
+
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["count", "--set", "--value", "2_000"])
+        .write_stdin(document)
+        .assert()
+        .success()
+        .stdout(document.replace("1_000", "2_000"));
+}
+
+#[test]
+fn delete_keeps_detached_comments() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package > version", "--delete"])
+        .write_stdin(indoc! {r#"
+            package {
+                name "kq"
+
+                // release
+
+                // the version
+                version "1.0.0"
+                edition "2021"
+            }
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            package {
+                name "kq"
+
+                // release
+
+                edition "2021"
+            }
+        "#});
+}
+
+#[test]
+fn delete_in_one_line() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["name", "--delete"])
+        .write_stdin("package { name \"kq\"; version \"1.0.0\"; }; workspace; name \"kq\"\n")
+        .assert()
+        .success()
+        .stdout("package { version \"1.0.0\"; }; workspace\n");
+}
+
+#[test]
+fn delete_last_in_line() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["b", "--delete"])
+        .write_stdin("a; b\nc; b\npackage { a; b\n}\n")
+        .assert()
+        .success()
+        .stdout("a\nc\npackage { a\n}\n");
+}
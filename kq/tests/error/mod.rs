@@ -0,0 +1,126 @@
+use assert_cmd::Command;
+use indoc::indoc;
+
+#[test]
+fn selector_expected_token() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("package[version")
+        .write_stdin("package")
+        .assert()
+        .code(3)
+        .stderr(indoc! {"
+            Error: invalid selector, expected `]` or an operator
+                package[version
+                               ^
+        "});
+}
+
+#[test]
+fn selector_offending_column() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"dependencies > [version ?= "1.0.0"]"#)
+        .write_stdin("package")
+        .assert()
+        .code(3)
+        .stderr(indoc! {r#"
+            Error: invalid selector, expected an operator
+                dependencies > [version ?= "1.0.0"]
+                                        ^
+        "#});
+}
+
+#[test]
+fn selector_missing_accessor() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("name ||")
+        .write_stdin("package")
+        .assert()
+        .code(3)
+        .stderr(indoc! {"
+            Error: invalid selector, expected an accessor
+                name ||
+                       ^
+        "});
+}
+
+#[test]
+fn selector_missing_after_combinator() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("package >")
+        .write_stdin("package")
+        .assert()
+        .code(3)
+        .stderr(indoc! {"
+            Error: invalid selector, expected a selector after `>`
+                package >
+                         ^
+        "});
+}
+
+#[test]
+fn selector_trailing_input() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("package, version")
+        .write_stdin("package")
+        .assert()
+        .code(3)
+        .stderr(indoc! {"
+            Error: invalid selector, expected the end of the selector
+                package, version
+                       ^
+        "});
+}
+
+#[test]
+fn unsupported_pseudo_class() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies > []:hover")
+        .write_stdin("package")
+        .assert()
+        .code(3)
+        .stderr(indoc! {"
+            Error: pseudo-class `:hover` is not supported
+                dependencies > []:hover
+                                 ^
+        "});
+}
+
+#[test]
+fn document() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("package")
+        .write_stdin(indoc! {r#"
+            package {
+                name "foo" {
+            }
+        "#})
+        .assert()
+        .code(4)
+        .stderr(indoc! {"
+            Error: (standard input):1:9: Expected closing '}' in node children block.
+                package {
+                        ^
+        "});
+}
+
+#[test]
+fn invalid_pattern() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg(r#"step[uses ~= "@v[0-9"]"#)
+        .write_stdin("package")
+        .assert()
+        .code(3)
+        .stderr(indoc! {r#"
+            Error: invalid regular expression, unclosed character class
+                step[uses ~= "@v[0-9"]
+                             ^
+        "#});
+}
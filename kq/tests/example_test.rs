@@ -51,7 +51,7 @@ fn descendant_by_identifier() {
         .success()
         .stdout(indoc! {r#"
             dependencies platform="windows" {
-                winapi "1.0.0" path=".\/crates\/my-winapi-fork"
+                winapi "1.0.0" path="./crates/my-winapi-fork"
             }
             dependencies {
                 miette "2.0.0" dev=true
@@ -69,7 +69,7 @@ fn identifier_and_implicit_property_name() {
         .success()
         .stdout(indoc! {r#"
             dependencies platform="windows" {
-                winapi "1.0.0" path=".\/crates\/my-winapi-fork"
+                winapi "1.0.0" path="./crates/my-winapi-fork"
             }
         "#});
 }
@@ -84,7 +84,7 @@ fn identifier_and_explicit_property_name() {
         .success()
         .stdout(indoc! {r#"
             dependencies platform="windows" {
-                winapi "1.0.0" path=".\/crates\/my-winapi-fork"
+                winapi "1.0.0" path="./crates/my-winapi-fork"
             }
         "#});
 }
@@ -98,7 +98,7 @@ fn all_direct_children() {
         .assert()
         .success()
         .stdout(indoc! {r#"
-            winapi "1.0.0" path=".\/crates\/my-winapi-fork"
+            winapi "1.0.0" path="./crates/my-winapi-fork"
             miette "2.0.0" dev=true
         "#});
 }
@@ -0,0 +1,90 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn match_without_exit_status() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("missing")
+        .write_stdin("package")
+        .assert()
+        .code(0)
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn match_with_exit_status() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-e", "package"])
+        .write_stdin("package")
+        .assert()
+        .code(0)
+        .stdout("package\n");
+}
+
+#[test]
+fn no_match_with_exit_status() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["--exit-status", "missing"])
+        .write_stdin("package")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn match_in_any_input() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args([
+            "-e",
+            "dependencies",
+            "tests/input/package.kdl",
+            "tests/input/workspace.kdl",
+        ])
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn invalid_option() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["--unknown", "package"])
+        .write_stdin("package")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Unrecognized option"));
+}
+
+#[test]
+fn missing_file() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package", "tests/exit_status/missing.kdl"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn invalid_selector() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-e", "package["])
+        .write_stdin("package")
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn invalid_document() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-e", "package"])
+        .write_stdin("package {")
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("(standard input)"));
+}
@@ -0,0 +1,118 @@
+use assert_cmd::Command;
+use indoc::indoc;
+
+#[test]
+fn file() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package > name", "tests/input/package.kdl"])
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            name "foo"
+        "#});
+}
+
+#[test]
+fn files() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args([
+            "package > name",
+            "tests/input/package.kdl",
+            "tests/input/workspace.kdl",
+        ])
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            name "foo"
+            name "bar"
+        "#});
+}
+
+#[test]
+fn stdin_and_file() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package > name", "-", "tests/input/package.kdl"])
+        .write_stdin(indoc! {r#"
+            package {
+                name "baz"
+            }
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            name "baz"
+            name "foo"
+        "#});
+}
+
+#[test]
+fn glob() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package > version", "tests/input/*.kdl"])
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            version "1.0.0"
+            version "2.0.0"
+        "#});
+}
+
+#[test]
+fn with_filename() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args([
+            "-H",
+            "dependencies || package > name",
+            "tests/input/package.kdl",
+            "tests/input/workspace.kdl",
+        ])
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            tests/input/package.kdl:name "foo"
+            tests/input/workspace.kdl:name "bar"
+            tests/input/workspace.kdl:dependencies {
+            tests/input/workspace.kdl:    foo "1.0.0"
+            tests/input/workspace.kdl:}
+        "#});
+}
+
+#[test]
+fn with_filename_stdin() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["--with-filename", "name"])
+        .write_stdin(indoc! {r#"
+            name "foo"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            (standard input):name "foo"
+        "#});
+}
+
+#[test]
+fn missing_file() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["name", "tests/input/missing.kdl"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("tests/input/missing.kdl"));
+}
+
+#[test]
+fn unmatched_glob() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["name", "tests/input/*.missing"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("tests/input/*.missing"));
+}
@@ -0,0 +1,97 @@
+use assert_cmd::Command;
+use indoc::indoc;
+
+#[test]
+fn with_location() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args([
+            "--with-location",
+            "dependencies > []",
+            "tests/location/package.kdl",
+        ])
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            tests/location/package.kdl:6:9: winapi "1.0.0"
+            tests/location/package.kdl:9:9: (dev)miette "2.0.0"
+        "#});
+}
+
+#[test]
+fn with_location_stdin() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["--with-location", "name"])
+        .write_stdin(indoc! {r#"
+            package { name "foo"; }
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            (standard input):1:11: name "foo"
+        "#});
+}
+
+#[test]
+fn with_location_children() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args([
+            "--with-location",
+            "dependencies[platform]",
+            "tests/location/package.kdl",
+        ])
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            tests/location/package.kdl:5:5: dependencies platform="windows" {
+                winapi "1.0.0"
+            }
+        "#});
+}
+
+#[test]
+fn with_location_and_paths() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args([
+            "--with-location",
+            "-p",
+            "version",
+            "tests/location/package.kdl",
+        ])
+        .assert()
+        .success()
+        .stdout(indoc! {"
+            tests/location/package.kdl:3:5: package[0]/version[0]
+        "});
+}
+
+#[test]
+fn with_location_and_map_operator() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args([
+            "--with-location",
+            "dependencies > [] => val()",
+            "tests/location/package.kdl",
+        ])
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            tests/location/package.kdl:6:9: "1.0.0"
+            tests/location/package.kdl:9:9: "2.0.0"
+        "#});
+}
+
+#[test]
+fn with_location_json() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-o", "json", "--with-location", "name"])
+        .write_stdin(r#"name "foo""#)
+        .assert()
+        .code(2)
+        .stderr("Error: --with-location can only be used with the kdl output format\n");
+}
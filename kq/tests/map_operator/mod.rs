@@ -0,0 +1,167 @@
+use assert_cmd::Command;
+use indoc::indoc;
+
+const INPUT: &str = indoc! {r#"
+    package {
+        name "foo"
+        version "1.0.0"
+        dependencies platform="windows" {
+            winapi "1.0.0" path="./crates/my-winapi-fork"
+        }
+        dependencies {
+            miette "2.0.0" dev=true
+        }
+    }
+"#};
+
+#[test]
+fn node_name() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies > [] => name()")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "winapi"
+            "miette"
+        "#});
+}
+
+#[test]
+fn val_without_index() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies > [] => val()")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "1.0.0"
+            "2.0.0"
+        "#});
+}
+
+#[test]
+fn val_absent() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("package > [] => val(1)")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            null
+            null
+            null
+            null
+        "#});
+}
+
+#[test]
+fn prop_explicit() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies => prop(platform)")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "windows"
+            null
+        "#});
+}
+
+#[test]
+fn prop_implicit() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("miette => dev")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            true
+        "#});
+}
+
+#[test]
+fn props() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies > [] => props()")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            path="./crates/my-winapi-fork"
+            dev=true
+        "#});
+}
+
+#[test]
+fn props_in_document_order() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("a => props()")
+        .write_stdin("a z=1 b=2 m=3 b=4")
+        .assert()
+        .success()
+        .stdout("z=1 m=3 b=4\n");
+}
+
+#[test]
+fn values() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("on => values()")
+        .write_stdin(indoc! {r#"
+            on "push" "pull_request"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "push" "pull_request"
+        "#});
+}
+
+#[test]
+fn tuple() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies > [] => (name(), val(), props())")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "winapi" "1.0.0" path="./crates/my-winapi-fork"
+            "miette" "2.0.0" dev=true
+        "#});
+}
+
+#[test]
+fn missing_accessor() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies =>")
+        .write_stdin(INPUT)
+        .assert()
+        .failure();
+}
+
+#[test]
+fn type_tag() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[] => (tag(), name())")
+        .write_stdin(indoc! {r#"
+            (author)name "Kat"
+            version "1.0.0"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "author" "name"
+            null "version"
+        "#});
+}
@@ -0,0 +1,110 @@
+use assert_cmd::Command;
+use indoc::indoc;
+
+const INPUT: &str = indoc! {r#"
+    package {
+        name "foo"
+        version "1.0.0"
+        dependencies platform="windows" {
+            winapi "1.0.0" path="./crates/my-winapi-fork"
+        }
+        dependencies {
+            miette "2.0.0" dev=true
+            kdl "4.0.0" dev=false
+        }
+    }
+"#};
+
+#[test]
+fn parent() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("winapi parent()")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            dependencies platform="windows" {
+                winapi "1.0.0" path="./crates/my-winapi-fork"
+            }
+        "#});
+}
+
+#[test]
+fn parent_deduplicated() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies > [] parent()")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            dependencies platform="windows" {
+                winapi "1.0.0" path="./crates/my-winapi-fork"
+            }
+            dependencies {
+                miette "2.0.0" dev=true
+                kdl "4.0.0" dev=false
+            }
+        "#});
+}
+
+#[test]
+fn parent_then_child() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("miette parent() > kdl")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            kdl "4.0.0" dev=false
+        "#});
+}
+
+#[test]
+fn ancestor() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("winapi ancestor(package) > name")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            name "foo"
+        "#});
+}
+
+#[test]
+fn ancestor_any() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("kdl ancestor() > version")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            version "1.0.0"
+        "#});
+}
+
+#[test]
+fn ancestor_not_found() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("name ancestor(dependencies)")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn parent_at_start() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("parent()")
+        .write_stdin(INPUT)
+        .assert()
+        .code(3);
+}
@@ -0,0 +1,137 @@
+use assert_cmd::Command;
+use indoc::indoc;
+use predicates::prelude::*;
+
+#[test]
+fn json() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["--output", "json", "package"])
+        .write_stdin(indoc! {r#"
+            package "kq" edition=2021 {
+                dependency "nom" version="7.0.0" optional=false
+            }
+            version "1.0.6"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            [
+              {
+                "name": "package",
+                "values": [
+                  "kq"
+                ],
+                "properties": {
+                  "edition": 2021
+                },
+                "children": [
+                  {
+                    "name": "dependency",
+                    "values": [
+                      "nom"
+                    ],
+                    "properties": {
+                      "version": "7.0.0",
+                      "optional": false
+                    },
+                    "children": []
+                  }
+                ]
+              }
+            ]
+        "#});
+}
+
+#[test]
+fn json_type_annotations() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["--output", "jsonl", "[]"])
+        .write_stdin(r#"(author)name "Kat" age=(u8)30"#)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            {"name":"name","type":"author","values":["Kat"],"properties":{"age":{"type":"u8","value":30}},"children":[]}
+        "#});
+}
+
+#[test]
+fn json_no_match() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["--output", "json", "missing"])
+        .write_stdin(indoc! {r#"
+            package "kq"
+        "#})
+        .assert()
+        .success()
+        .stdout("[]\n");
+}
+
+#[test]
+fn json_lines() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["--output", "jsonl", "dependency"])
+        .write_stdin(indoc! {r#"
+            dependency "nom" ratio=1.5
+            dependency "kdl" path=null
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            {"name":"dependency","values":["nom"],"properties":{"ratio":1.5},"children":[]}
+            {"name":"dependency","values":["kdl"],"properties":{"path":null},"children":[]}
+        "#});
+}
+
+#[test]
+fn json_lines_with_map_operator() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-o", "jsonl", "dependency => (name(), val(), props())"])
+        .write_stdin(indoc! {r#"
+            dependency "nom" version="7.0.0"
+            dependency "kdl" version="4.7.1"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            ["dependency","nom",{"version":"7.0.0"}]
+            ["dependency","kdl",{"version":"4.7.1"}]
+        "#});
+}
+
+#[test]
+fn json_props_in_document_order() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-o", "jsonl", "a => props()"])
+        .write_stdin("a z=1 b=2 m=3")
+        .assert()
+        .success()
+        .stdout("{\"z\":1,\"b\":2,\"m\":3}\n");
+}
+
+#[test]
+fn unknown_format() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["--output", "yaml", "package"])
+        .write_stdin("package")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown output format: yaml"));
+}
+
+#[test]
+fn json_with_filename() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-o", "jsonl", "-H", "name"])
+        .write_stdin(r#"name "kq""#)
+        .assert()
+        .code(2)
+        .stderr("Error: --with-filename can only be used with the kdl output format\n");
+}
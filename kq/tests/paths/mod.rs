@@ -0,0 +1,103 @@
+use assert_cmd::Command;
+use indoc::indoc;
+
+#[test]
+fn paths() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["--paths", "dependencies > []"])
+        .write_stdin(indoc! {r#"
+            package {
+                dependencies platform="windows" {
+                    winapi "1.0.0"
+                }
+                dependencies {
+                    miette "2.0.0"
+                    winapi "2.0.0"
+                }
+            }
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {"
+            package[0]/dependencies[0]/winapi[0]
+            package[0]/dependencies[1]/miette[0]
+            package[0]/dependencies[1]/winapi[0]
+        "});
+}
+
+#[test]
+fn identical_nodes() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-p", "name"])
+        .write_stdin(indoc! {r#"
+            package {
+                name "foo"
+                name "foo"
+            }
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {"
+            package[0]/name[0]
+            package[0]/name[1]
+        "});
+}
+
+#[test]
+fn quoted_name() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-p", "[]"])
+        .write_stdin(indoc! {r#"
+            "crates/kq" "1.0.0"
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "crates/kq"[0]
+        "#});
+}
+
+#[test]
+fn with_filename() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-p", "-H", "version", "tests/input/package.kdl"])
+        .assert()
+        .success()
+        .stdout(indoc! {"
+            tests/input/package.kdl:package[0]/version[0]
+        "});
+}
+
+#[test]
+fn json_lines() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-p", "-o", "jsonl", "version || name"])
+        .write_stdin(indoc! {r#"
+            package {
+                name "foo"
+                version "1.0.0"
+            }
+        "#})
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            "package[0]/name[0]"
+            "package[0]/version[0]"
+        "#});
+}
+
+#[test]
+fn mapping() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["--paths", "name => val()"])
+        .write_stdin(r#"name "foo""#)
+        .assert()
+        .code(2)
+        .stderr("Error: --paths can not be combined with the map operator `=>`\n");
+}
@@ -0,0 +1,264 @@
+use assert_cmd::Command;
+use indoc::indoc;
+
+const INPUT: &str = indoc! {r#"
+    package {
+        name "foo"
+        version "1.0.0"
+        dependencies platform="windows" {
+            winapi "1.0.0" path="./crates/my-winapi-fork"
+        }
+        dependencies {
+            miette "2.0.0" dev=true
+            kdl "4.0.0" dev=false
+        }
+    }
+"#};
+
+#[test]
+fn not_matcher() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies > []:not([dev = true])")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            winapi "1.0.0" path="./crates/my-winapi-fork"
+            kdl "4.0.0" dev=false
+        "#});
+}
+
+#[test]
+fn not_name() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("package > []:not(dependencies)")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            name "foo"
+            version "1.0.0"
+        "#});
+}
+
+#[test]
+fn name_not_matcher() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies:not([platform])")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            dependencies {
+                miette "2.0.0" dev=true
+                kdl "4.0.0" dev=false
+            }
+        "#});
+}
+
+#[test]
+fn nested_not() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies > []:not([dev]:not([dev = false]))")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            winapi "1.0.0" path="./crates/my-winapi-fork"
+            kdl "4.0.0" dev=false
+        "#});
+}
+
+#[test]
+fn not_with_siblings() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("name + []:not(dependencies)")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            version "1.0.0"
+        "#});
+}
+
+#[test]
+fn first_child() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("package > []:first-child")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            name "foo"
+        "#});
+}
+
+#[test]
+fn last_child() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies > []:last-child")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            winapi "1.0.0" path="./crates/my-winapi-fork"
+            kdl "4.0.0" dev=false
+        "#});
+}
+
+#[test]
+fn nth_child() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("package > []:nth-child(2n + 1)")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            name "foo"
+            dependencies platform="windows" {
+                winapi "1.0.0" path="./crates/my-winapi-fork"
+            }
+        "#});
+}
+
+#[test]
+fn nth_of_type() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies:nth-of-type(2)")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            dependencies {
+                miette "2.0.0" dev=true
+                kdl "4.0.0" dev=false
+            }
+        "#});
+}
+
+#[test]
+fn empty() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("package > []:empty")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            name "foo"
+            version "1.0.0"
+        "#});
+}
+
+#[test]
+fn invalid_nth() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[]:nth-child(first)")
+        .write_stdin(INPUT)
+        .assert()
+        .code(3)
+        .stderr(indoc! {"
+            Error: invalid selector, expected `an+b`
+                []:nth-child(first)
+                             ^
+        "});
+}
+
+#[test]
+fn has() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("package:has(miette[dev = true])")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            package {
+                name "foo"
+                version "1.0.0"
+                dependencies platform="windows" {
+                    winapi "1.0.0" path="./crates/my-winapi-fork"
+                }
+                dependencies {
+                    miette "2.0.0" dev=true
+                    kdl "4.0.0" dev=false
+                }
+            }
+        "#});
+}
+
+#[test]
+fn has_child() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[]:has(> winapi)")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            dependencies platform="windows" {
+                winapi "1.0.0" path="./crates/my-winapi-fork"
+            }
+        "#});
+}
+
+#[test]
+fn not_has() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("dependencies:not(:has([dev]))")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            dependencies platform="windows" {
+                winapi "1.0.0" path="./crates/my-winapi-fork"
+            }
+        "#});
+}
+
+#[test]
+fn has_only_looks_inside() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("[]:has(name)")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            package {
+                name "foo"
+                version "1.0.0"
+                dependencies platform="windows" {
+                    winapi "1.0.0" path="./crates/my-winapi-fork"
+                }
+                dependencies {
+                    miette "2.0.0" dev=true
+                    kdl "4.0.0" dev=false
+                }
+            }
+        "#});
+}
+
+#[test]
+fn has_child_matcher() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .arg("package:has(dependencies > [dev = true]) > name")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            name "foo"
+        "#});
+}
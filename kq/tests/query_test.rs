@@ -0,0 +1,245 @@
+use kdl::{KdlDocument, KdlEntry, KdlValue};
+use kq::{Edit, Error, Location, Output, Query};
+use std::thread;
+
+#[test]
+fn apply_to_several_documents() {
+    let query = Query::parse("package > version => val()").unwrap();
+    let documents = [
+        r#"package { version "1.0.0"; }"#,
+        r#"package { version "2.0.0"; }"#,
+    ]
+    .map(|document| document.parse::<KdlDocument>().unwrap());
+
+    let outputs = documents
+        .iter()
+        .flat_map(|document| query.apply(document.nodes()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        outputs,
+        vec![
+            Output::Value(KdlValue::String("1.0.0".to_owned())),
+            Output::Value(KdlValue::String("2.0.0".to_owned())),
+        ]
+    );
+}
+
+#[test]
+fn apply_without_selector() {
+    let query = Query::parse("").unwrap();
+    let document = "package; workspace".parse::<KdlDocument>().unwrap();
+
+    assert_eq!(query.apply(document.nodes()).len(), 2);
+}
+
+#[test]
+fn share_across_threads() {
+    let query = Query::parse("dependencies > []").unwrap();
+    let document = "dependencies { miette; kdl; }"
+        .parse::<KdlDocument>()
+        .unwrap();
+
+    thread::scope(|scope| {
+        let handles = (0..4)
+            .map(|_| scope.spawn(|| query.apply(document.nodes()).len()))
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 2);
+        }
+    });
+}
+
+#[test]
+fn parse_error() {
+    assert_eq!(
+        Query::parse("package[version"),
+        Err(Error::Selector {
+            offset: 15,
+            expected: vec!["`]`", "an operator"]
+        })
+    );
+}
+
+#[test]
+fn select_borrows_from_document() {
+    let query = Query::parse("dependencies > [] => name()").unwrap();
+    let document = "dependencies { miette; kdl; }"
+        .parse::<KdlDocument>()
+        .unwrap();
+    let dependencies = document.nodes()[0].children().unwrap().nodes();
+
+    let nodes = query.select(document.nodes());
+
+    assert_eq!(nodes.len(), 2);
+    assert!(std::ptr::eq(nodes[0], &dependencies[0]));
+    assert!(std::ptr::eq(nodes[1], &dependencies[1]));
+}
+
+#[test]
+fn select_document() {
+    let document = r#"package { name "foo"; version "1.0.0"; }"#.parse::<KdlDocument>().unwrap();
+
+    let nodes = kq::select_document("version || name", document.nodes()).unwrap();
+
+    assert_eq!(
+        nodes
+            .iter()
+            .map(|node| node.name().value())
+            .collect::<Vec<_>>(),
+        vec!["name", "version"]
+    );
+    assert_eq!(
+        kq::query_document("version || name", document.nodes().to_vec()).unwrap(),
+        nodes.into_iter().cloned().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn select_with_paths() {
+    let query = Query::parse("dependencies > []").unwrap();
+    let document = "package { dependencies { miette; }; dependencies { kdl; miette; }; }"
+        .parse::<KdlDocument>()
+        .unwrap();
+
+    let paths = query
+        .select_with_paths(document.nodes())
+        .into_iter()
+        .map(|(path, node)| (path.to_string(), node.name().value().to_owned()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        paths,
+        vec![
+            (
+                "package[0]/dependencies[0]/miette[0]".to_owned(),
+                "miette".to_owned()
+            ),
+            (
+                "package[0]/dependencies[1]/kdl[0]".to_owned(),
+                "kdl".to_owned()
+            ),
+            (
+                "package[0]/dependencies[1]/miette[0]".to_owned(),
+                "miette".to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn path_segments() {
+    let query = Query::parse("dependencies").unwrap();
+    let document = "package { dependencies; dependencies; }"
+        .parse::<KdlDocument>()
+        .unwrap();
+
+    let paths = query.select_with_paths(document.nodes());
+
+    assert_eq!(
+        paths[1].0.segments(),
+        [("package".to_owned(), 0), ("dependencies".to_owned(), 1)]
+    );
+}
+
+#[test]
+fn location() {
+    let source = "package {\n    name \"foo\"\n    // comment\n    /* ü */ version \"1.0.0\"\n}\n";
+    let document = source.parse::<KdlDocument>().unwrap();
+    let query = Query::parse("version").unwrap();
+
+    let nodes = query.select(document.nodes());
+
+    assert_eq!(
+        Location::of(nodes[0], source),
+        Location {
+            offset: 53,
+            length: 15,
+            line: 4,
+            column: 13,
+        }
+    );
+}
+
+#[test]
+fn edit() {
+    let query = Query::parse("dependencies > [] => prop(optional)").unwrap();
+    let mut document = "package { dependencies { nom optional=false; kdl; }; }"
+        .parse::<KdlDocument>()
+        .unwrap();
+
+    let edit = Edit::Set(KdlEntry::new(true));
+    let count = query.edit(&mut document, &edit).unwrap();
+
+    assert_eq!(count, 2);
+    assert_eq!(
+        document.to_string(),
+        "package { dependencies { nom optional=true; kdl optional=true; }; }"
+    );
+}
+
+#[test]
+fn edit_tuple() {
+    let query = Query::parse("package => (val(), prop(edition))").unwrap();
+    let mut document = "package".parse::<KdlDocument>().unwrap();
+
+    assert_eq!(
+        query.edit(&mut document, &Edit::Delete),
+        Err(Error::Unsupported {
+            offset: 11,
+            feature: "editing a tuple".to_owned()
+        })
+    );
+}
+
+#[test]
+fn edit_keeps_untouched_text() {
+    let source =
+        "/* header */\npackage  {\n\tversion 0x10 // hex\n\n\tsize 1_000\n}\r\nworkspace\n";
+    let query = Query::parse("package > size").unwrap();
+    let mut document = source.parse::<KdlDocument>().unwrap();
+
+    query
+        .edit(&mut document, &Edit::Rename("length".to_owned()))
+        .unwrap();
+
+    assert_eq!(document.to_string(), source.replace("size", "length"));
+}
+
+#[test]
+fn update() {
+    let query = Query::parse("dependencies > [] |= prop(dev) = false, values() += 0x10").unwrap();
+    let mut document = "dependencies { nom \"7\"; kdl dev=true; }"
+        .parse::<KdlDocument>()
+        .unwrap();
+
+    let count = query.update(&mut document);
+
+    assert!(query.has_update());
+    assert_eq!(count, 2);
+    assert_eq!(
+        document.to_string(),
+        "dependencies { nom \"7\" dev=false 0x10; kdl dev=false 0x10; }"
+    );
+}
+
+#[test]
+fn update_by_query_document() {
+    let document = "package { version \"1.0.0\"; }; workspace"
+        .parse::<KdlDocument>()
+        .unwrap();
+
+    let nodes = kq::query_document("version |= val() = \"2.0.0\"", document.nodes().to_vec());
+
+    assert_eq!(
+        nodes.map(|nodes| nodes
+            .iter()
+            .map(|node| node.to_string())
+            .collect::<Vec<_>>()),
+        Ok(vec![
+            "package { version \"2.0.0\"; }; ".to_owned(),
+            "workspace".to_owned()
+        ])
+    );
+}
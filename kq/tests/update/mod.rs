@@ -0,0 +1,103 @@
+use assert_cmd::Command;
+use indoc::indoc;
+
+const PACKAGE: &str = indoc! {r#"
+    package {
+        version "1.0.0" // bumped by CI
+        dependencies {
+            miette "2.0.0" dev=true
+            nom "7"
+        }
+    }
+    "#};
+
+#[test]
+fn set_property() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["dependencies > [] |= prop(dev) = false"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            package {
+                version "1.0.0" // bumped by CI
+                dependencies {
+                    miette "2.0.0" dev=false
+                    nom "7" dev=false
+                }
+            }
+        "#});
+}
+
+#[test]
+fn set_argument() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["package > version |= val() = (semver)\"2.0.0\""])
+        .write_stdin(PACKAGE)
+        .assert()
+        .success()
+        .stdout(PACKAGE.replace(r#""1.0.0""#, r#"(semver)"2.0.0""#));
+}
+
+#[test]
+fn set_argument_past_the_last() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["a |= val(3) = 2"])
+        .write_stdin("a 1 x=0\n")
+        .assert()
+        .success()
+        .stdout("a 1 x=0 null null 2\n");
+}
+
+#[test]
+fn several_assignments() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["miette |= del(dev), values() += 0x10, name() = \"miette-fork\""])
+        .write_stdin(PACKAGE)
+        .assert()
+        .success()
+        .stdout(PACKAGE.replace(r#"miette "2.0.0" dev=true"#, r#"miette-fork "2.0.0" 0x10"#));
+}
+
+#[test]
+fn no_match() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["-e", "workspace |= val() = 1"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(1)
+        .stdout(PACKAGE);
+}
+
+#[test]
+fn unsupported_assignment() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["miette |= del(dev), props() = 1"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(3)
+        .stderr(indoc! {"
+            Error: setting `props()` is not supported
+                miette |= del(dev), props() = 1
+                                    ^
+        "});
+}
+
+#[test]
+fn combined_with_edit() {
+    Command::cargo_bin("kq")
+        .unwrap()
+        .args(["miette |= del(dev)", "--delete"])
+        .write_stdin(PACKAGE)
+        .assert()
+        .code(2)
+        .stderr(
+            "Error: the update operator `|=` can not be combined with --set, --delete or --rename\n",
+        );
+}